@@ -76,6 +76,97 @@ fn main() {
             .write_to_file(out_path)
             .expect("Couldn't write bindings!");
     }
+
+    #[cfg(feature = "generate_shaders")]
+    generate_shaders();
+}
+
+/// Translate the WGSL shaders in `src/shaders/` to the GLSL and SPIR-V files next to them.
+#[cfg(feature = "generate_shaders")]
+fn generate_shaders() {
+    use naga::{back, front, valid, ResourceBinding, ShaderStage};
+
+    let shader_path = format!("{}/src/shaders/", env!("CARGO_MANIFEST_DIR"));
+    let read = |name: &str| {
+        println!("cargo:rerun-if-changed={}{}", shader_path, name);
+        std::fs::read_to_string(format!("{}{}", shader_path, name)).unwrap()
+    };
+    let common = read("common.wgsl");
+
+    for name in ["fill", "fill_path"] {
+        let source = format!("{}{}", common, read(&format!("{}.wgsl", name)));
+        let module = front::wgsl::parse_str(&source)
+            .unwrap_or_else(|e| panic!("{}.wgsl: {}", name, e.emit_to_string(&source)));
+        let info = valid::Validator::new(valid::ValidationFlags::all(), Default::default())
+            .validate(&module)
+            .unwrap_or_else(|e| panic!("{}.wgsl: {:?}", name, e));
+
+        let spirv_options = back::spv::Options {
+            flags: back::spv::WriterFlags::ADJUST_COORDINATE_SPACE
+                | back::spv::WriterFlags::LABEL_VARYINGS
+                | back::spv::WriterFlags::CLAMP_FRAG_DEPTH,
+            ..Default::default()
+        };
+        let spirv = back::spv::write_vec(&module, &info, &spirv_options, None)
+            .expect("Couldn't write SPIR-V");
+        let spirv = spirv
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<u8>>();
+        std::fs::write(format!("{}{}.spv", shader_path, name), spirv).unwrap();
+
+        // Textures and the sampler are combined in GLSL, each texture keeps its own unit.
+        let glsl_options = back::glsl::Options {
+            version: back::glsl::Version::Desktop(450),
+            binding_map: [
+                (
+                    ResourceBinding {
+                        group: 0,
+                        binding: 0,
+                    },
+                    0,
+                ),
+                (
+                    ResourceBinding {
+                        group: 1,
+                        binding: 0,
+                    },
+                    0,
+                ),
+                (
+                    ResourceBinding {
+                        group: 1,
+                        binding: 1,
+                    },
+                    1,
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        for (stage, entry_point, extension) in [
+            (ShaderStage::Vertex, "vs_main", "vert"),
+            (ShaderStage::Fragment, "fs_main", "frag"),
+        ] {
+            let pipeline_options = back::glsl::PipelineOptions {
+                shader_stage: stage,
+                entry_point: entry_point.to_string(),
+                multiview: None,
+            };
+            let mut glsl = String::new();
+            back::glsl::Writer::new(
+                &mut glsl,
+                &module,
+                &info,
+                &glsl_options,
+                &pipeline_options,
+                Default::default(),
+            )
+            .and_then(|mut writer| writer.write())
+            .expect("Couldn't write GLSL");
+            std::fs::write(format!("{}{}.{}", shader_path, name, extension), glsl).unwrap();
+        }
+    }
 }
 
 #[cfg(feature = "requires_dll")]
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ultralight::{Config, Renderer, ViewConfig};
 
 /// Extremely simple example loading and rendering page.html
@@ -14,11 +16,11 @@ pub fn main() {
     view.load_url("file:///page.html".to_owned());
 
     // Wait for page to be loaded.
-    while !view.is_ready() {
-        renderer.update();
-    }
+    renderer
+        .wait_for_load(&view, Duration::from_secs(10))
+        .unwrap();
 
     renderer.render();
 
-    view.get_image().save("test.png").unwrap();
+    view.get_image().unwrap().save("test.png").unwrap();
 }
@@ -1,4 +1,4 @@
-use std::{ffi::CString, ptr::null_mut};
+use std::{ffi::CString, ptr::null_mut, time::Duration};
 
 use ultralight::{sys::*, Config, Renderer, ViewConfig};
 
@@ -14,7 +14,7 @@ pub fn main() {
     let mut view: ultralight::View = renderer.create_view(800, 800, &ViewConfig::default());
 
     view.load_url("file:///javascript.html".to_owned());
-    view.set_dom_ready_callback(|view| {
+    view.set_dom_ready_callback(|view, _frame| {
         unsafe {
             let context = &view.lock_jscontext();
             let global_object = &context.get_global_object();
@@ -35,21 +35,21 @@ pub fn main() {
     });
 
     // Wait for page to be loaded.
-    while !view.is_ready() {
-        renderer.update();
-    }
+    renderer
+        .wait_for_load(&view, Duration::from_secs(10))
+        .unwrap();
 
     view.mouse_pressed(400, 400, true);
     view.mouse_pressed(400, 400, false);
 
     for _ in 0..30 {
         renderer.update();
-        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(10));
     }
 
     renderer.render();
 
-    view.get_image().save("test.png").unwrap();
+    view.get_image().unwrap().save("test.png").unwrap();
 }
 
 extern "C" fn get_message(
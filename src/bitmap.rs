@@ -0,0 +1,505 @@
+use crate::sys::{
+    ulBitmapErase, ulBitmapGetBpp, ulBitmapGetFormat, ulBitmapGetHeight, ulBitmapGetRowBytes,
+    ulBitmapGetSize, ulBitmapGetWidth, ulBitmapIsEmpty, ulBitmapLockPixels, ulBitmapOwnsPixels,
+    ulBitmapSwapRedBlueChannels, ulBitmapUnlockPixels, ulBitmapWritePNG, ulCreateBitmap,
+    ulCreateBitmapFromCopy, ulCreateBitmapFromPixels, ulCreateEmptyBitmap, ulDestroyBitmap,
+    ULBitmap, ULBitmapFormat, ULBitmapFormat_kBitmapFormat_A8_UNORM,
+    ULBitmapFormat_kBitmapFormat_BGRA8_UNORM_SRGB,
+};
+use std::{
+    ffi::CString,
+    fmt,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+/// Pixel format of a `Bitmap`.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BitmapFormat {
+    /// Alpha channel only, 8 bits per pixel.
+    A8_UNORM,
+    /// Blue, green, red, alpha. 8 bits per channel, sRGB gamma encoded and premultiplied alpha.
+    BGRA8_UNORM_SRGB,
+}
+
+impl BitmapFormat {
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            Self::A8_UNORM => 1,
+            Self::BGRA8_UNORM_SRGB => 4,
+        }
+    }
+
+    pub(crate) fn from_raw(value: ULBitmapFormat) -> Option<Self> {
+        if value == ULBitmapFormat_kBitmapFormat_A8_UNORM {
+            Some(Self::A8_UNORM)
+        } else if value == ULBitmapFormat_kBitmapFormat_BGRA8_UNORM_SRGB {
+            Some(Self::BGRA8_UNORM_SRGB)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<BitmapFormat> for ULBitmapFormat {
+    fn from(value: BitmapFormat) -> Self {
+        match value {
+            BitmapFormat::A8_UNORM => ULBitmapFormat_kBitmapFormat_A8_UNORM,
+            BitmapFormat::BGRA8_UNORM_SRGB => ULBitmapFormat_kBitmapFormat_BGRA8_UNORM_SRGB,
+        }
+    }
+}
+
+/// Why `Bitmap::from_pixels` can't create a bitmap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitmapError {
+    /// `row_bytes` is smaller than a row of pixels.
+    RowBytesTooSmall { row_bytes: u32, width_bytes: u64 },
+    /// `pixels` is shorter than `row_bytes * height`.
+    NotEnoughPixels { expected: u64, len: usize },
+}
+
+impl fmt::Display for BitmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowBytesTooSmall {
+                row_bytes,
+                width_bytes,
+            } => write!(
+                f,
+                "row_bytes {} is smaller than a row of {} bytes",
+                row_bytes, width_bytes
+            ),
+            Self::NotEnoughPixels { expected, len } => {
+                write!(f, "expected {} bytes of pixels, got {}", expected, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BitmapError {}
+
+/// Owned Ultralight bitmap.
+///
+/// Rows can be padded, always use `row_bytes` when walking the pixels.
+pub struct Bitmap {
+    inner: ULBitmap,
+}
+
+impl Bitmap {
+    /// Create an empty bitmap without any pixels.
+    pub fn empty() -> Self {
+        Self {
+            inner: unsafe { ulCreateEmptyBitmap() },
+        }
+    }
+
+    /// Create a zeroed bitmap with certain dimensions and pixel format.
+    pub fn new(width: u32, height: u32, format: BitmapFormat) -> Self {
+        Self {
+            inner: unsafe { ulCreateBitmap(width, height, format.into()) },
+        }
+    }
+
+    /// Create a bitmap with a size but without pixels, like the ones Ultralight passes to
+    /// `GpuDriver::create_texture` for render targets. `is_empty` returns true for it.
+    pub fn render_target(width: u32, height: u32, format: BitmapFormat) -> Self {
+        let row_bytes = width.saturating_mul(format.bytes_per_pixel());
+        Self {
+            inner: unsafe {
+                ulCreateBitmapFromPixels(
+                    width,
+                    height,
+                    format.into(),
+                    row_bytes,
+                    std::ptr::null(),
+                    0,
+                    false,
+                )
+            },
+        }
+    }
+
+    /// Create a bitmap by copying `pixels`, each row is `row_bytes` long.
+    pub fn from_pixels(
+        width: u32,
+        height: u32,
+        format: BitmapFormat,
+        row_bytes: u32,
+        pixels: &[u8],
+    ) -> Result<Self, BitmapError> {
+        // In u64 these can't overflow.
+        let width_bytes = u64::from(width) * u64::from(format.bytes_per_pixel());
+        if u64::from(row_bytes) < width_bytes {
+            return Err(BitmapError::RowBytesTooSmall {
+                row_bytes,
+                width_bytes,
+            });
+        }
+        let expected = u64::from(row_bytes) * u64::from(height);
+        if (pixels.len() as u64) < expected {
+            return Err(BitmapError::NotEnoughPixels {
+                expected,
+                len: pixels.len(),
+            });
+        }
+
+        Ok(Self {
+            inner: unsafe {
+                ulCreateBitmapFromPixels(
+                    width,
+                    height,
+                    format.into(),
+                    row_bytes,
+                    pixels.as_ptr() as _,
+                    pixels.len(),
+                    true,
+                )
+            },
+        })
+    }
+
+    /// Take ownership of a raw bitmap, it is destroyed when dropped.
+    ///
+    /// # Safety
+    /// `inner` must be a valid bitmap that nothing else destroys.
+    pub unsafe fn from_raw(inner: ULBitmap) -> Self {
+        Self { inner }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { ulBitmapGetWidth(self.inner) }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { ulBitmapGetHeight(self.inner) }
+    }
+
+    /// `None` for formats this crate doesn't know yet.
+    pub fn format(&self) -> Option<BitmapFormat> {
+        BitmapFormat::from_raw(unsafe { ulBitmapGetFormat(self.inner) })
+    }
+
+    /// Bytes per pixel.
+    pub fn bpp(&self) -> u32 {
+        unsafe { ulBitmapGetBpp(self.inner) }
+    }
+
+    /// Bytes per row, including padding.
+    pub fn row_bytes(&self) -> u32 {
+        unsafe { ulBitmapGetRowBytes(self.inner) }
+    }
+
+    /// Size in bytes of the pixel buffer.
+    pub fn size(&self) -> usize {
+        unsafe { ulBitmapGetSize(self.inner) }
+    }
+
+    pub fn owns_pixels(&self) -> bool {
+        unsafe { ulBitmapOwnsPixels(self.inner) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { ulBitmapIsEmpty(self.inner) }
+    }
+
+    /// Lock the pixels for reading, they're unlocked when the guard is dropped.
+    pub fn lock_pixels(&self) -> LockedPixels<'_> {
+        let (pixels, size) = self.lock();
+        LockedPixels {
+            pixels: unsafe { std::slice::from_raw_parts(pixels, size) },
+            bitmap: self,
+        }
+    }
+
+    /// Lock the pixels for writing, they're unlocked when the guard is dropped.
+    pub fn lock_pixels_mut(&mut self) -> LockedPixelsMut<'_> {
+        let (pixels, size) = self.lock();
+        LockedPixelsMut {
+            pixels: unsafe { std::slice::from_raw_parts_mut(pixels, size) },
+            bitmap: self,
+        }
+    }
+
+    fn lock(&self) -> (*mut u8, usize) {
+        let pixels = unsafe { ulBitmapLockPixels(self.inner) } as *mut u8;
+        if pixels.is_null() {
+            (std::ptr::NonNull::dangling().as_ptr(), 0)
+        } else {
+            (pixels, self.size())
+        }
+    }
+
+    /// Reset all pixels to 0.
+    pub fn erase(&mut self) {
+        unsafe { ulBitmapErase(self.inner) };
+    }
+
+    /// Convert between BGRA and RGBA in place.
+    pub fn swap_red_blue_channels(&mut self) {
+        unsafe { ulBitmapSwapRedBlueChannels(self.inner) };
+    }
+
+    /// Write the bitmap to a PNG file.
+    pub fn write_png(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref().to_string_lossy().into_owned();
+        let path = CString::new(path)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+        if unsafe { ulBitmapWritePNG(self.inner, path.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(std::io::Error::other("Ultralight failed to write PNG"))
+        }
+    }
+}
+
+impl Clone for Bitmap {
+    /// Deep copy of the bitmap and its pixels.
+    fn clone(&self) -> Self {
+        Self {
+            inner: unsafe { ulCreateBitmapFromCopy(self.inner) },
+        }
+    }
+}
+
+impl Drop for Bitmap {
+    fn drop(&mut self) {
+        unsafe {
+            ulDestroyBitmap(self.inner);
+        }
+    }
+}
+
+impl From<&Bitmap> for ULBitmap {
+    fn from(value: &Bitmap) -> Self {
+        value.inner
+    }
+}
+
+impl std::fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bitmap")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("format", &self.format())
+            .field("row_bytes", &self.row_bytes())
+            .finish()
+    }
+}
+
+/// Bitmap owned by Ultralight, it is never destroyed by us.
+pub struct BitmapRef<'a> {
+    bitmap: ManuallyDrop<Bitmap>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl BitmapRef<'_> {
+    /// # Safety
+    /// `inner` must stay a valid bitmap for the lifetime of the reference.
+    pub unsafe fn from_raw(inner: ULBitmap) -> Self {
+        Self {
+            bitmap: ManuallyDrop::new(Bitmap { inner }),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Deref for BitmapRef<'_> {
+    type Target = Bitmap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bitmap
+    }
+}
+
+/// Locked pixels of a `Bitmap`, see `Bitmap::lock_pixels`.
+pub struct LockedPixels<'a> {
+    bitmap: &'a Bitmap,
+    pixels: &'a [u8],
+}
+
+impl LockedPixels<'_> {
+    /// Iterate over the rows, padding at the end of each row is excluded.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        rows(self.bitmap, self.pixels)
+    }
+
+    /// Copy the pixels into a tightly packed buffer without row padding.
+    pub fn to_packed_vec(&self) -> Vec<u8> {
+        self.rows().flatten().copied().collect()
+    }
+}
+
+impl Deref for LockedPixels<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.pixels
+    }
+}
+
+impl Drop for LockedPixels<'_> {
+    fn drop(&mut self) {
+        unsafe { ulBitmapUnlockPixels(self.bitmap.inner) };
+    }
+}
+
+/// Mutably locked pixels of a `Bitmap`, see `Bitmap::lock_pixels_mut`.
+pub struct LockedPixelsMut<'a> {
+    bitmap: &'a Bitmap,
+    pixels: &'a mut [u8],
+}
+
+impl LockedPixelsMut<'_> {
+    /// Iterate over the rows, padding at the end of each row is excluded.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        rows(self.bitmap, self.pixels)
+    }
+
+    /// Iterate mutably over the rows, padding at the end of each row is excluded.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let (row_bytes, width_bytes, height) = row_layout(self.bitmap);
+
+        // A last row cut short by the buffer is skipped.
+        self.pixels
+            .chunks_mut(row_bytes.max(1))
+            .take(height)
+            .filter_map(move |row| row.get_mut(..width_bytes))
+    }
+}
+
+impl Deref for LockedPixelsMut<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.pixels
+    }
+}
+
+impl DerefMut for LockedPixelsMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pixels
+    }
+}
+
+impl Drop for LockedPixelsMut<'_> {
+    fn drop(&mut self) {
+        unsafe { ulBitmapUnlockPixels(self.bitmap.inner) };
+    }
+}
+
+fn rows<'a>(bitmap: &Bitmap, pixels: &'a [u8]) -> impl Iterator<Item = &'a [u8]> {
+    let (row_bytes, width_bytes, height) = row_layout(bitmap);
+
+    // A last row cut short by the buffer is skipped.
+    pixels
+        .chunks(row_bytes.max(1))
+        .take(height)
+        .filter_map(move |row| row.get(..width_bytes))
+}
+
+/// Row stride, bytes of pixels per row and row count of `bitmap`.
+fn row_layout(bitmap: &Bitmap) -> (usize, usize, usize) {
+    (
+        bitmap.row_bytes() as usize,
+        bitmap.width() as usize * bitmap.bpp() as usize,
+        bitmap.height() as usize,
+    )
+}
+
+/// Pixel layout to export a `Bitmap` to, see `Bitmap::read_pixels`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    Rgba8,
+    Bgra8,
+    Rgb8,
+    /// Single channel luminance.
+    Gray8,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            Self::Rgba8 | Self::Bgra8 => 4,
+            Self::Rgb8 => 3,
+            Self::Gray8 => 1,
+        }
+    }
+}
+
+/// How color channels relate to alpha in exported pixels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AlphaMode {
+    /// Colors are independent of alpha, what most image formats expect.
+    Straight,
+    /// Colors are multiplied by alpha, what Ultralight renders.
+    Premultiplied,
+}
+
+impl Bitmap {
+    /// Copy the pixels into a tightly packed buffer of `format`.
+    ///
+    /// The bitmap itself is never modified. `None` when the bitmap's format is unknown.
+    pub fn read_pixels(&self, format: PixelFormat, alpha: AlphaMode) -> Option<Vec<u8>> {
+        let bitmap_format = self.format()?;
+        let pixels = self.lock_pixels();
+
+        // A size that overflows can't be allocated anyway, `out` grows on demand then.
+        let capacity = (self.width() as usize)
+            .checked_mul(self.height() as usize)
+            .and_then(|pixels| pixels.checked_mul(format.bytes_per_pixel() as usize));
+        let mut out = Vec::with_capacity(capacity.unwrap_or(0));
+        for row in pixels.rows() {
+            match bitmap_format {
+                BitmapFormat::A8_UNORM => {
+                    for &a in row {
+                        write_pixel(&mut out, format, alpha, [a, a, a, a]);
+                    }
+                }
+                BitmapFormat::BGRA8_UNORM_SRGB => {
+                    for bgra in row.chunks_exact(4) {
+                        write_pixel(
+                            &mut out,
+                            format,
+                            alpha,
+                            [bgra[2], bgra[1], bgra[0], bgra[3]],
+                        );
+                    }
+                }
+            }
+        }
+
+        Some(out)
+    }
+}
+
+/// Append a premultiplied `rgba` pixel to `out`.
+fn write_pixel(out: &mut Vec<u8>, format: PixelFormat, alpha: AlphaMode, rgba: [u8; 4]) {
+    let [r, g, b, a] = match alpha {
+        AlphaMode::Premultiplied => rgba,
+        AlphaMode::Straight => unpremultiply(rgba),
+    };
+
+    match format {
+        PixelFormat::Rgba8 => out.extend_from_slice(&[r, g, b, a]),
+        PixelFormat::Bgra8 => out.extend_from_slice(&[b, g, r, a]),
+        PixelFormat::Rgb8 => out.extend_from_slice(&[r, g, b]),
+        PixelFormat::Gray8 => {
+            let luma = (299 * r as u32 + 587 * g as u32 + 114 * b as u32 + 500) / 1000;
+            out.push(luma as u8);
+        }
+    }
+}
+
+fn unpremultiply([r, g, b, a]: [u8; 4]) -> [u8; 4] {
+    if a == 0 {
+        return [0, 0, 0, 0];
+    }
+
+    let channel = |c: u8| ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8;
+    [channel(r), channel(g), channel(b), a]
+}
@@ -1,82 +1,127 @@
-use std::{
-    any::Any,
-    sync::{Mutex, OnceLock},
-};
+use std::{any::Any, cell::RefCell};
+
+mod batch;
+mod command;
+mod diff;
+mod render_target;
+mod resource;
+mod software;
+mod trace;
+mod validation;
+mod vertex;
+
+pub use batch::*;
+pub use command::*;
+pub use diff::*;
+pub use render_target::*;
+pub use resource::*;
+pub use software::*;
+pub use trace::*;
+pub use validation::*;
+pub use vertex::*;
 
-use crate::sys::{
-    ulPlatformSetGPUDriver, C_Bitmap, ULCommandList, ULGPUDriver, ULIndexBuffer, ULRenderBuffer,
-    ULVertexBuffer,
+use crate::{
+    sys::{
+        ulPlatformSetGPUDriver, C_Bitmap, ULCommandList, ULGPUDriver, ULIndexBuffer,
+        ULRenderBuffer, ULVertexBuffer,
+    },
+    Bitmap, BitmapRef, IntRect,
 };
 
-unsafe extern "C" fn begin_synchronize() {}
-unsafe extern "C" fn end_synchronize() {}
+thread_local! {
+    /// Ultralight calls the driver from the thread calling `Renderer::render`,
+    /// so the driver lives there instead of behind a lock.
+    static GPU_DRIVER: RefCell<Option<Box<dyn GpuDriver>>> = RefCell::new(None);
+}
+
+/// Run `f` on the driver of the current thread.
+fn dispatch<R>(f: impl FnOnce(&mut dyn GpuDriver) -> R) -> R {
+    GPU_DRIVER.with(|driver| {
+        let mut driver = driver
+            .try_borrow_mut()
+            .expect("Gpu driver called re-entrantly");
+        let driver = driver
+            .as_mut()
+            .expect("Gpu driver enabled but not set on this thread?");
+        f(driver.as_mut())
+    })
+}
+
+unsafe extern "C" fn begin_synchronize() {
+    dispatch(|driver| driver.begin_synchronize());
+}
+unsafe extern "C" fn end_synchronize() {
+    dispatch(|driver| driver.end_synchronize());
+}
 unsafe extern "C" fn next_texture_id() -> u32 {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.next_texture_id()
+    dispatch(|driver| driver.next_texture_id())
 }
 unsafe extern "C" fn create_texture(id: u32, bitmap: *mut C_Bitmap) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.create_texture(id, bitmap);
+    dispatch(|driver| driver.create_texture(id, &BitmapRef::from_raw(bitmap)));
 }
 unsafe extern "C" fn update_texture(id: u32, bitmap: *mut C_Bitmap) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.update_texture(id, bitmap);
+    dispatch(|driver| driver.update_texture(id, &BitmapRef::from_raw(bitmap)));
 }
 unsafe extern "C" fn destroy_texture(id: u32) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.destroy_texture(id);
+    dispatch(|driver| driver.destroy_texture(id));
 }
 unsafe extern "C" fn next_render_buffer_id() -> u32 {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.next_render_buffer_id()
+    dispatch(|driver| driver.next_render_buffer_id())
 }
 unsafe extern "C" fn create_render_buffer(id: u32, render_buffer: ULRenderBuffer) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.create_render_buffer(id, render_buffer);
+    dispatch(|driver| driver.create_render_buffer(id, render_buffer));
 }
 unsafe extern "C" fn destroy_render_buffer(id: u32) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.destroy_render_buffer(id);
+    dispatch(|driver| driver.destroy_render_buffer(id));
 }
 unsafe extern "C" fn next_geometry_id() -> u32 {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.next_geometry_id()
+    dispatch(|driver| driver.next_geometry_id())
 }
 unsafe extern "C" fn create_geometry(id: u32, vb: ULVertexBuffer, ib: ULIndexBuffer) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.create_geometry(id, vb, ib);
+    dispatch(|driver| driver.create_geometry(id, VertexBuffer::from_raw(vb), index_slice(ib)));
 }
 unsafe extern "C" fn update_geometry(id: u32, vb: ULVertexBuffer, ib: ULIndexBuffer) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.update_geometry(id, vb, ib);
+    dispatch(|driver| driver.update_geometry(id, VertexBuffer::from_raw(vb), index_slice(ib)));
 }
 unsafe extern "C" fn destroy_geometry(id: u32) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.destroy_geometry(id);
+    dispatch(|driver| driver.destroy_geometry(id));
 }
 unsafe extern "C" fn update_command_list(cmd_list: ULCommandList) {
-    let mut driver = static_gpu_driver().lock().unwrap();
-    let driver = driver.as_mut().expect("Gpu driver enabled but not set?");
-    driver.update_command_list(cmd_list);
+    dispatch(|driver| driver.update_command_list(CommandList::from_raw(cmd_list)));
 }
 
-pub trait GpuDriver: Send + Sync + Any {
+/// Lets a `dyn GpuDriver` be downcast to its concrete type, implemented for every type.
+pub trait AsAny: Any {
     fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub trait GpuDriver: AsAny {
+    /// Called before Ultralight starts dispatching driver calls during `Renderer::render`.
+    fn begin_synchronize(&mut self) {}
+    /// Called after the last driver call of `Renderer::render`.
+    fn end_synchronize(&mut self) {}
 
     fn next_texture_id(&mut self) -> u32;
-    fn create_texture(&mut self, id: u32, bitmap: *mut C_Bitmap);
-    fn update_texture(&mut self, id: u32, bitmap: *mut C_Bitmap);
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap);
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap);
+    /// Like `update_texture`, but only pixels inside `region` changed since the last upload.
+    ///
+    /// Called by `DiffingDriver`, falls back to a full `update_texture` by default.
+    fn update_texture_region(&mut self, id: u32, bitmap: &Bitmap, region: IntRect) {
+        let _ = region;
+        self.update_texture(id, bitmap);
+    }
     fn destroy_texture(&mut self, id: u32);
 
     fn next_render_buffer_id(&mut self) -> u32;
@@ -84,16 +129,36 @@ pub trait GpuDriver: Send + Sync + Any {
     fn destroy_render_buffer(&mut self, id: u32);
 
     fn next_geometry_id(&mut self) -> u32;
-    fn create_geometry(&mut self, id: u32, vb: ULVertexBuffer, ib: ULIndexBuffer);
-    fn update_geometry(&mut self, id: u32, vb: ULVertexBuffer, ib: ULIndexBuffer);
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]);
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]);
+    /// Like `update_geometry`, but only the bytes in `changes` differ from the last upload.
+    ///
+    /// `vertices` and `indices` still hold the whole geometry, which keeps its size. Called by
+    /// `DiffingDriver`, falls back to a full `update_geometry` by default.
+    fn update_geometry_ranges(
+        &mut self,
+        id: u32,
+        vertices: VertexBuffer<'_>,
+        indices: &[u32],
+        changes: &GeometryChanges,
+    ) {
+        let _ = changes;
+        self.update_geometry(id, vertices, indices);
+    }
     fn destroy_geometry(&mut self, id: u32);
 
-    fn update_command_list(&mut self, cmd_list: ULCommandList);
+    fn update_command_list(&mut self, commands: CommandList<'_>);
 }
 
+/// Use `driver` for GPU accelerated views, replacing the previous driver.
+///
+/// Has to be called on the thread that creates the `Renderer` and calls `Renderer::render`.
 pub fn set_gpu_driver(driver: Box<dyn GpuDriver>) {
-    let mut static_driver = static_gpu_driver().lock().unwrap();
-    *static_driver = Some(driver);
+    GPU_DRIVER.with(|static_driver| {
+        *static_driver
+            .try_borrow_mut()
+            .expect("Can't replace the gpu driver from inside a driver call") = Some(driver);
+    });
 
     unsafe {
         let driver = ULGPUDriver {
@@ -116,7 +181,21 @@ pub fn set_gpu_driver(driver: Box<dyn GpuDriver>) {
     }
 }
 
-pub fn static_gpu_driver() -> &'static Mutex<Option<Box<dyn GpuDriver>>> {
-    static ARRAY: OnceLock<Mutex<Option<Box<dyn GpuDriver>>>> = OnceLock::new();
-    ARRAY.get_or_init(|| Mutex::new(None))
+/// Run `f` on the driver of the current thread if it is a `D`.
+///
+/// Returns `None` when no driver is set, it is another type, or it is called from inside a driver call.
+pub fn with_gpu_driver<D: GpuDriver, R>(f: impl FnOnce(&mut D) -> R) -> Option<R> {
+    GPU_DRIVER.with(|driver| {
+        let mut driver = driver.try_borrow_mut().ok()?;
+        // Deref the box first, otherwise we'd downcast the `Box` itself.
+        let driver: &mut dyn GpuDriver = &mut **driver.as_mut()?;
+        Some(f(driver.as_any_mut().downcast_mut::<D>()?))
+    })
+}
+
+/// Remove the driver from the current thread and give it back.
+///
+/// Only do this once every `Renderer` using it has been dropped.
+pub fn take_gpu_driver() -> Option<Box<dyn GpuDriver>> {
+    GPU_DRIVER.with(|driver| driver.try_borrow_mut().ok()?.take())
 }
@@ -0,0 +1,377 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{CommandList, GeometryId, GpuCommand, GpuState, RenderBufferId, ShaderType, TextureId};
+use crate::{shaders::Uniforms, IntRect};
+
+/// Words of the `GpuState` fields `Uniforms` are built from: viewport, clip count, transform,
+/// scalars, vectors and clips.
+const UNIFORM_KEY_WORDS: usize = 2 + 1 + 16 + 8 + 8 * 4 + 8 * 16;
+
+/// State a driver has to bake into a pipeline, everything else can change between draws.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    pub shader_type: ShaderType,
+    pub enable_blend: bool,
+    /// Whether scissor testing is enabled, for APIs where it's part of the pipeline.
+    pub enable_scissor: bool,
+}
+
+impl From<&GpuState> for PipelineKey {
+    fn from(state: &GpuState) -> Self {
+        Self {
+            shader_type: state.shader_type,
+            enable_blend: state.enable_blend,
+            enable_scissor: state.scissor.is_some(),
+        }
+    }
+}
+
+/// Draw of a `BatchedPass`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchedDraw {
+    pub geometry_id: GeometryId,
+    pub indices: Range<u32>,
+    pub pipeline: PipelineKey,
+    /// Index into `CommandBatch::uniforms`.
+    pub uniforms: usize,
+    /// `texture_1` to `texture_3`, all `None` when texturing is disabled.
+    pub textures: [Option<TextureId>; 3],
+    pub scissor: Option<IntRect>,
+    pub viewport_width: u32,
+    pub viewport_height: u32,
+}
+
+impl BatchedDraw {
+    /// Whether `next` draws the indices right after ours with the same state.
+    fn continues_with(&self, next: &BatchedDraw) -> bool {
+        self.geometry_id == next.geometry_id
+            && self.indices.end == next.indices.start
+            && self.pipeline == next.pipeline
+            && self.uniforms == next.uniforms
+            && self.textures == next.textures
+            && self.scissor == next.scissor
+            && self.viewport_width == next.viewport_width
+            && self.viewport_height == next.viewport_height
+    }
+}
+
+/// Consecutive commands rendering to one render buffer, a single render pass for most APIs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchedPass {
+    pub render_buffer_id: RenderBufferId,
+    /// Whether the render buffer is cleared to transparent black before the draws.
+    pub clear: bool,
+    pub draws: Vec<BatchedDraw>,
+}
+
+/// Command list grouped into passes, with deduplicated uniforms and merged draws.
+///
+/// Ultralight emits many small draws that often share their state. Consecutive commands targeting
+/// the same render buffer end up in one pass, a clear starts a new pass. Draws continuing the
+/// indices of the previous draw with identical state are merged, and draws with identical uniforms
+/// share the same entry in `uniforms`, so each only has to be uploaded once per command list.
+/// Batching doesn't call into Ultralight, `Uniforms` are only built by `uniforms`.
+///
+/// Keep the batch around and call `update` for each command list to reuse its allocations.
+#[derive(Debug, Default, Clone)]
+pub struct CommandBatch {
+    passes: Vec<BatchedPass>,
+    /// State of the first draw using each distinct uniforms.
+    uniform_states: Vec<GpuState>,
+    uniform_indices: HashMap<[u32; UNIFORM_KEY_WORDS], usize>,
+}
+
+impl CommandBatch {
+    pub fn new(commands: CommandList<'_>) -> Self {
+        let mut batch = Self::default();
+        batch.update(commands);
+        batch
+    }
+
+    /// Replace the batch with `commands`.
+    pub fn update(&mut self, commands: CommandList<'_>) {
+        self.passes.clear();
+        self.uniform_states.clear();
+        self.uniform_indices.clear();
+
+        for command in commands {
+            match command {
+                GpuCommand::ClearRenderBuffer { render_buffer_id } => self.clear(render_buffer_id),
+                GpuCommand::DrawGeometry {
+                    geometry_id,
+                    indices,
+                    state,
+                } => self.draw(geometry_id, indices, &state),
+            }
+        }
+    }
+
+    pub fn passes(&self) -> &[BatchedPass] {
+        &self.passes
+    }
+
+    /// Distinct uniforms of the draws, in order of first use, see `Uniforms::new`.
+    pub fn uniforms(&self, scale: f32, time: f32) -> impl ExactSizeIterator<Item = Uniforms> + '_ {
+        self.uniform_states
+            .iter()
+            .map(move |state| Uniforms::new(state, scale, time))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    fn clear(&mut self, render_buffer_id: RenderBufferId) {
+        match self.passes.last_mut() {
+            // Nothing was drawn yet, clearing again is the same as clearing once.
+            Some(pass) if pass.render_buffer_id == render_buffer_id && pass.draws.is_empty() => {
+                pass.clear = true
+            }
+            _ => self.passes.push(BatchedPass {
+                render_buffer_id,
+                clear: true,
+                draws: Vec::new(),
+            }),
+        }
+    }
+
+    fn draw(&mut self, geometry_id: GeometryId, indices: Range<u32>, state: &GpuState) {
+        if indices.is_empty() {
+            return;
+        }
+
+        let draw = BatchedDraw {
+            geometry_id,
+            indices,
+            pipeline: PipelineKey::from(state),
+            uniforms: self.uniform_index(state),
+            textures: if state.enable_texturing {
+                [state.texture_1, state.texture_2, state.texture_3]
+            } else {
+                [None; 3]
+            },
+            scissor: state.scissor,
+            viewport_width: state.viewport_width,
+            viewport_height: state.viewport_height,
+        };
+
+        let pass = match self.passes.last_mut() {
+            Some(pass) if pass.render_buffer_id == state.render_buffer_id => pass,
+            _ => {
+                self.passes.push(BatchedPass {
+                    render_buffer_id: state.render_buffer_id,
+                    clear: false,
+                    draws: Vec::new(),
+                });
+                self.passes.last_mut().unwrap()
+            }
+        };
+
+        match pass.draws.last_mut() {
+            Some(last) if last.continues_with(&draw) => last.indices.end = draw.indices.end,
+            _ => pass.draws.push(draw),
+        }
+    }
+
+    fn uniform_index(&mut self, state: &GpuState) -> usize {
+        // Compare the bits, so uniforms holding NaN can still be shared.
+        let floats = state
+            .transform
+            .iter()
+            .chain(&state.uniform_scalar)
+            .chain(state.uniform_vector.iter().flatten())
+            .chain(state.clip().iter().flatten());
+        let words = [
+            state.viewport_width,
+            state.viewport_height,
+            state.clip().len() as u32,
+        ]
+        .into_iter()
+        .chain(floats.map(|value| value.to_bits()));
+        let mut key = [0; UNIFORM_KEY_WORDS];
+        for (word, value) in key.iter_mut().zip(words) {
+            *word = value;
+        }
+
+        *self.uniform_indices.entry(key).or_insert_with(|| {
+            self.uniform_states.push(*state);
+            self.uniform_states.len() - 1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::{
+        ULCommand, ULCommandList, ULCommandType_kCommandType_ClearRenderBuffer,
+        ULCommandType_kCommandType_DrawGeometry,
+    };
+
+    fn clear(render_buffer_id: RenderBufferId) -> ULCommand {
+        let mut command = ULCommand {
+            command_type: ULCommandType_kCommandType_ClearRenderBuffer as _,
+            ..Default::default()
+        };
+        command.gpu_state.render_buffer_id = render_buffer_id;
+        command
+    }
+
+    fn draw(
+        render_buffer_id: RenderBufferId,
+        geometry_id: GeometryId,
+        indices: Range<u32>,
+    ) -> ULCommand {
+        let mut command = ULCommand {
+            command_type: ULCommandType_kCommandType_DrawGeometry as _,
+            geometry_id,
+            indices_offset: indices.start,
+            indices_count: indices.end - indices.start,
+            ..Default::default()
+        };
+        command.gpu_state.render_buffer_id = render_buffer_id;
+        command.gpu_state.viewport_width = 4;
+        command.gpu_state.viewport_height = 4;
+        command
+    }
+
+    fn batch(mut commands: Vec<ULCommand>) -> CommandBatch {
+        let list = ULCommandList {
+            size: commands.len() as u32,
+            commands: commands.as_mut_ptr(),
+        };
+        CommandBatch::new(unsafe { CommandList::from_raw(list) })
+    }
+
+    /// `(render_buffer_id, clear, geometry and indices of every draw)` of a pass.
+    type PassLayout = (RenderBufferId, bool, Vec<(GeometryId, Range<u32>)>);
+
+    fn layout(batch: &CommandBatch) -> Vec<PassLayout> {
+        batch
+            .passes()
+            .iter()
+            .map(|pass| {
+                let draws = pass
+                    .draws
+                    .iter()
+                    .map(|draw| (draw.geometry_id, draw.indices.clone()))
+                    .collect();
+                (pass.render_buffer_id, pass.clear, draws)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn passes_follow_render_buffers() {
+        let batch = batch(vec![
+            clear(1),
+            draw(1, 10, 0..3),
+            draw(1, 11, 0..3),
+            draw(2, 10, 3..6),
+            clear(1),
+            draw(1, 10, 6..9),
+        ]);
+        assert_eq!(
+            layout(&batch),
+            [
+                (1, true, vec![(10, 0..3), (11, 0..3)]),
+                (2, false, vec![(10, 3..6)]),
+                (1, true, vec![(10, 6..9)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn clears_start_new_passes() {
+        let batch = batch(vec![clear(1), clear(1), draw(1, 10, 0..3), clear(1)]);
+        assert_eq!(
+            layout(&batch),
+            [(1, true, vec![(10, 0..3)]), (1, true, vec![])]
+        );
+    }
+
+    #[test]
+    fn consecutive_draws_merge() {
+        let mut blended = draw(1, 10, 9..12);
+        blended.gpu_state.enable_blend = true;
+        let batch = batch(vec![
+            draw(1, 10, 0..3),
+            draw(1, 10, 3..6),
+            draw(1, 10, 7..9),
+            blended,
+            draw(1, 10, 12..12),
+        ]);
+        assert_eq!(
+            layout(&batch),
+            [(1, false, vec![(10, 0..6), (10, 7..9), (10, 9..12)])]
+        );
+    }
+
+    #[test]
+    fn uniforms_are_deduplicated() {
+        let mut scaled = draw(1, 11, 0..3);
+        scaled.gpu_state.uniform_scalar[0] = 2.0;
+        let mut nan = draw(1, 12, 0..3);
+        nan.gpu_state.uniform_scalar[0] = f32::NAN;
+        let batch = batch(vec![
+            draw(1, 10, 0..3),
+            scaled,
+            nan,
+            draw(2, 10, 3..6),
+            scaled,
+            nan,
+        ]);
+
+        let uniforms: Vec<_> = batch
+            .passes()
+            .iter()
+            .flat_map(|pass| pass.draws.iter().map(|draw| draw.uniforms))
+            .collect();
+        assert_eq!(uniforms, [0, 1, 2, 0, 1, 2]);
+        assert_eq!(batch.uniforms(1.0, 0.0).len(), 3);
+    }
+
+    #[test]
+    fn uniforms_follow_state() {
+        let mut moved = draw(1, 10, 0..3);
+        moved.gpu_state.transform.data[12] = 1.0;
+        let mut resized = draw(1, 10, 0..3);
+        resized.gpu_state.viewport_width = 8;
+        let mut clipped = draw(1, 10, 0..3);
+        clipped.gpu_state.clip_size = 1;
+        // Only active clips count.
+        let mut unused_clip = draw(1, 10, 0..3);
+        unused_clip.gpu_state.clip[1].data[0] = 1.0;
+        let mut textured = draw(1, 10, 0..3);
+        textured.gpu_state.enable_texturing = true;
+
+        let batch = batch(vec![
+            draw(1, 10, 0..3),
+            moved,
+            resized,
+            clipped,
+            unused_clip,
+            textured,
+        ]);
+        let uniforms: Vec<_> = batch.passes()[0]
+            .draws
+            .iter()
+            .map(|draw| draw.uniforms)
+            .collect();
+        assert_eq!(uniforms, [0, 1, 2, 3, 0, 0]);
+    }
+
+    #[test]
+    fn textures_need_texturing() {
+        let mut textured = draw(1, 10, 0..3);
+        textured.gpu_state.texture_1_id = 5;
+        let mut untextured = textured;
+        textured.gpu_state.enable_texturing = true;
+        untextured.indices_offset = 3;
+
+        let batch = batch(vec![textured, untextured]);
+        let draws = &batch.passes()[0].draws;
+        assert_eq!(draws[0].textures, [Some(5), None, None]);
+        assert_eq!(draws[1].textures, [None; 3]);
+    }
+}
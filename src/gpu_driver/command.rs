@@ -0,0 +1,233 @@
+use std::ops::Range;
+
+use crate::{
+    sys::{
+        ULCommand, ULCommandList, ULCommandType_kCommandType_ClearRenderBuffer,
+        ULCommandType_kCommandType_DrawGeometry, ULGPUState, ULShaderType_kShaderType_Fill,
+        ULShaderType_kShaderType_FillPath,
+    },
+    IntRect,
+};
+
+pub type TextureId = u32;
+pub type RenderBufferId = u32;
+pub type GeometryId = u32;
+
+/// Vertex/pixel shader pair a draw command should be rendered with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ShaderType {
+    /// Used for quads, vertices are `Vertex2f4ub2f2f28f`.
+    Fill,
+    /// Used for paths, vertices are `Vertex2f4ub2f`.
+    FillPath,
+}
+
+impl ShaderType {
+    pub(crate) fn from_raw(value: u8) -> Option<Self> {
+        let value = value as i32;
+        if value == ULShaderType_kShaderType_Fill {
+            Some(Self::Fill)
+        } else if value == ULShaderType_kShaderType_FillPath {
+            Some(Self::FillPath)
+        } else {
+            None
+        }
+    }
+}
+
+/// Safe copy of `ULGPUState`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GpuState {
+    pub viewport_width: u32,
+    pub viewport_height: u32,
+    /// Transform matrix, still needs to be multiplied with the screen-space projection.
+    pub transform: [f32; 16],
+    pub enable_texturing: bool,
+    pub enable_blend: bool,
+    pub shader_type: ShaderType,
+    pub render_buffer_id: RenderBufferId,
+    pub texture_1: Option<TextureId>,
+    pub texture_2: Option<TextureId>,
+    pub texture_3: Option<TextureId>,
+    pub uniform_scalar: [f32; 8],
+    pub uniform_vector: [[f32; 4]; 8],
+    /// `None` when scissor testing is disabled.
+    pub scissor: Option<IntRect>,
+    clip: [[f32; 16]; 8],
+    clip_size: u8,
+}
+
+impl GpuState {
+    /// `None` when the shader type is unknown.
+    pub fn from_raw(state: &ULGPUState) -> Option<Self> {
+        Some(Self::new(state, ShaderType::from_raw(state.shader_type)?))
+    }
+
+    /// Copy `state`, with `shader_type` in place of its raw one.
+    pub(crate) fn new(state: &ULGPUState, shader_type: ShaderType) -> Self {
+        let texture = |id: u32| if id == 0 { None } else { Some(id) };
+
+        Self {
+            viewport_width: state.viewport_width,
+            viewport_height: state.viewport_height,
+            transform: state.transform.data,
+            enable_texturing: state.enable_texturing,
+            enable_blend: state.enable_blend,
+            shader_type,
+            render_buffer_id: state.render_buffer_id,
+            texture_1: texture(state.texture_1_id),
+            texture_2: texture(state.texture_2_id),
+            texture_3: texture(state.texture_3_id),
+            uniform_scalar: state.uniform_scalar,
+            uniform_vector: state.uniform_vector.map(|v| v.value),
+            scissor: state
+                .enable_scissor
+                .then(|| IntRect::from(state.scissor_rect)),
+            clip: state.clip.map(|m| m.data),
+            clip_size: state.clip_size,
+        }
+    }
+
+    /// Clip matrices used by the pixel shader, only the active ones are returned.
+    pub fn clip(&self) -> &[[f32; 16]] {
+        &self.clip[..(self.clip_size as usize).min(self.clip.len())]
+    }
+}
+
+/// A single command from a `CommandList`.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum GpuCommand {
+    /// Clear the render buffer to transparent black.
+    ClearRenderBuffer { render_buffer_id: RenderBufferId },
+    /// Draw `indices` of a geometry into `state.render_buffer_id`.
+    DrawGeometry {
+        geometry_id: GeometryId,
+        indices: Range<u32>,
+        state: GpuState,
+    },
+}
+
+impl GpuCommand {
+    pub(crate) fn from_raw(cmd: &ULCommand) -> Option<Self> {
+        let command_type = cmd.command_type as i32;
+        if command_type == ULCommandType_kCommandType_ClearRenderBuffer {
+            Some(Self::ClearRenderBuffer {
+                render_buffer_id: cmd.gpu_state.render_buffer_id,
+            })
+        } else if command_type == ULCommandType_kCommandType_DrawGeometry {
+            // Ranges that overflow and unknown shaders can't be drawn, `ValidationDriver` reports
+            // them.
+            let indices_end = cmd.indices_offset.checked_add(cmd.indices_count)?;
+            Some(Self::DrawGeometry {
+                geometry_id: cmd.geometry_id,
+                indices: cmd.indices_offset..indices_end,
+                state: GpuState::from_raw(&cmd.gpu_state)?,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Render buffer this command writes to.
+    pub fn render_buffer_id(&self) -> RenderBufferId {
+        match self {
+            Self::ClearRenderBuffer { render_buffer_id } => *render_buffer_id,
+            Self::DrawGeometry { state, .. } => state.render_buffer_id,
+        }
+    }
+}
+
+/// Borrowed view of a `ULCommandList`, only valid during `GpuDriver::update_command_list`.
+#[derive(Copy, Clone)]
+pub struct CommandList<'a> {
+    commands: &'a [ULCommand],
+}
+
+impl<'a> CommandList<'a> {
+    /// # Safety
+    /// `list.commands` must point to `list.size` valid commands for the lifetime `'a`.
+    pub unsafe fn from_raw(list: ULCommandList) -> Self {
+        let commands = if list.commands.is_null() || list.size == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(list.commands, list.size as usize)
+        };
+
+        Self { commands }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Iterate over the commands, commands with an unknown type, an unknown shader type or an
+    /// overflowing index range are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = GpuCommand> + 'a {
+        self.into_iter()
+    }
+
+    pub fn raw(&self) -> &'a [ULCommand] {
+        self.commands
+    }
+}
+
+impl<'a> IntoIterator for CommandList<'a> {
+    type Item = GpuCommand;
+    type IntoIter =
+        std::iter::FilterMap<std::slice::Iter<'a, ULCommand>, fn(&ULCommand) -> Option<GpuCommand>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands
+            .iter()
+            .filter_map(GpuCommand::from_raw as fn(&ULCommand) -> Option<GpuCommand>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw() -> ULCommand {
+        let mut command = ULCommand {
+            command_type: ULCommandType_kCommandType_DrawGeometry as _,
+            indices_count: 3,
+            ..Default::default()
+        };
+        command.gpu_state.shader_type = ULShaderType_kShaderType_FillPath as _;
+        command
+    }
+
+    #[test]
+    fn undrawable_commands_are_skipped() {
+        let mut overflow = draw();
+        overflow.indices_offset = u32::MAX;
+        let mut unknown_shader = draw();
+        unknown_shader.gpu_state.shader_type = 99;
+        let mut unknown_type = draw();
+        unknown_type.command_type = 99;
+
+        let mut commands = [draw(), overflow, unknown_shader, unknown_type];
+        let list = ULCommandList {
+            size: commands.len() as u32,
+            commands: commands.as_mut_ptr(),
+        };
+        let commands: Vec<_> = unsafe { CommandList::from_raw(list) }.iter().collect();
+        assert_eq!(commands.len(), 1);
+        assert!(matches!(
+            commands[0],
+            GpuCommand::DrawGeometry {
+                indices: Range { start: 0, end: 3 },
+                state: GpuState {
+                    shader_type: ShaderType::FillPath,
+                    ..
+                },
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,318 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{vertex::as_bytes, CommandList, GeometryId, GpuDriver, TextureId, VertexBuffer};
+use crate::{sys::ULRenderBuffer, Bitmap, BitmapFormat, IntRect};
+
+/// Bytes of a geometry that changed since its last upload, see `GpuDriver::update_geometry_ranges`.
+///
+/// Ranges are 4 byte aligned and empty when nothing changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeometryChanges {
+    /// Changed bytes of `VertexBuffer::as_bytes`.
+    pub vertices: Range<usize>,
+    /// Changed bytes of the indices.
+    pub indices: Range<usize>,
+}
+
+impl GeometryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.indices.is_empty()
+    }
+}
+
+/// Range of `new` that differs from `old`, compared in 4 byte words.
+///
+/// Both have to be the same length.
+pub fn changed_range(old: &[u8], new: &[u8]) -> Range<usize> {
+    debug_assert_eq!(old.len(), new.len());
+    let mut words = old.chunks(4).zip(new.chunks(4));
+    let Some(first) = words.position(|(old, new)| old != new) else {
+        return 0..0;
+    };
+    // `position` stopped right after the first difference, search the rest from the back.
+    let last = words
+        .rposition(|(old, new)| old != new)
+        .map_or(first, |last| first + 1 + last);
+    first * 4..((last + 1) * 4).min(new.len())
+}
+
+/// Pixels of `new` that differ from `old`, both tightly packed `width` pixels wide.
+///
+/// Returns an empty rect when nothing changed.
+pub fn changed_rect(old: &[u8], new: &[u8], width: u32, bytes_per_pixel: u32) -> IntRect {
+    debug_assert_eq!(old.len(), new.len());
+    let row_bytes = (width * bytes_per_pixel).max(1) as usize;
+    let bytes_per_pixel = bytes_per_pixel.max(1) as usize;
+
+    let mut rect: Option<IntRect> = None;
+    for (y, (old, new)) in old.chunks(row_bytes).zip(new.chunks(row_bytes)).enumerate() {
+        let Some(left) = old.iter().zip(new).position(|(old, new)| old != new) else {
+            continue;
+        };
+        let right = old
+            .iter()
+            .zip(new)
+            .rposition(|(old, new)| old != new)
+            .unwrap();
+        let (left, right, y) = (
+            (left / bytes_per_pixel) as i32,
+            (right / bytes_per_pixel + 1) as i32,
+            y as i32,
+        );
+
+        let rect = rect.get_or_insert(IntRect {
+            left,
+            top: y,
+            right,
+            bottom: y + 1,
+        });
+        rect.left = rect.left.min(left);
+        rect.right = rect.right.max(right);
+        rect.bottom = y + 1;
+    }
+    rect.unwrap_or_default()
+}
+
+struct CachedGeometry {
+    stride: u32,
+    vertices: Vec<u8>,
+    indices: Vec<u32>,
+}
+
+struct CachedTexture {
+    width: u32,
+    height: u32,
+    format: Option<BitmapFormat>,
+    /// Tightly packed, without row padding.
+    pixels: Vec<u8>,
+}
+
+/// `GpuDriver` wrapper that only passes the parts of geometry and textures that changed to `inner`.
+///
+/// Keeps a copy of every vertex, index and pixel buffer it forwards. Updates are compared against
+/// it and forwarded as `update_geometry_ranges` and `update_texture_region`, or dropped when
+/// nothing changed. Updates changing the size or format of a resource are forwarded unchanged.
+/// Worth it for drivers that implement the partial updates, for animated content most of a
+/// geometry is the same from frame to frame.
+pub struct DiffingDriver<D> {
+    inner: D,
+    geometries: HashMap<GeometryId, CachedGeometry>,
+    textures: HashMap<TextureId, CachedTexture>,
+}
+
+impl<D: GpuDriver> DiffingDriver<D> {
+    pub fn new(inner: D) -> Self {
+        Self {
+            inner,
+            geometries: HashMap::new(),
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    fn cache_geometry(&mut self, id: GeometryId, vertices: &VertexBuffer<'_>, indices: &[u32]) {
+        self.geometries.insert(
+            id,
+            CachedGeometry {
+                stride: vertices.stride(),
+                vertices: vertices.as_bytes().to_vec(),
+                indices: indices.to_vec(),
+            },
+        );
+    }
+
+    fn cache_texture(&mut self, id: TextureId, bitmap: &Bitmap) {
+        // Render targets are written by the GPU, there is nothing to compare against.
+        if bitmap.is_empty() {
+            self.textures.remove(&id);
+            return;
+        }
+
+        self.textures.insert(
+            id,
+            CachedTexture {
+                width: bitmap.width(),
+                height: bitmap.height(),
+                format: bitmap.format(),
+                pixels: bitmap.lock_pixels().to_packed_vec(),
+            },
+        );
+    }
+}
+
+impl<D: GpuDriver> GpuDriver for DiffingDriver<D> {
+    fn begin_synchronize(&mut self) {
+        self.inner.begin_synchronize();
+    }
+
+    fn end_synchronize(&mut self) {
+        self.inner.end_synchronize();
+    }
+
+    fn next_texture_id(&mut self) -> u32 {
+        self.inner.next_texture_id()
+    }
+
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        self.cache_texture(id, bitmap);
+        self.inner.create_texture(id, bitmap);
+    }
+
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        let Some(cached) = self.textures.get_mut(&id).filter(|cached| {
+            cached.width == bitmap.width()
+                && cached.height == bitmap.height()
+                && cached.format == bitmap.format()
+        }) else {
+            self.cache_texture(id, bitmap);
+            self.inner.update_texture(id, bitmap);
+            return;
+        };
+
+        let pixels = bitmap.lock_pixels().to_packed_vec();
+        let region = changed_rect(&cached.pixels, &pixels, bitmap.width(), bitmap.bpp());
+        if region.is_empty() {
+            return;
+        }
+        cached.pixels = pixels;
+        self.inner.update_texture_region(id, bitmap, region);
+    }
+
+    fn destroy_texture(&mut self, id: u32) {
+        self.textures.remove(&id);
+        self.inner.destroy_texture(id);
+    }
+
+    fn next_render_buffer_id(&mut self) -> u32 {
+        self.inner.next_render_buffer_id()
+    }
+
+    fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+        self.inner.create_render_buffer(id, render_buffer);
+    }
+
+    fn destroy_render_buffer(&mut self, id: u32) {
+        self.inner.destroy_render_buffer(id);
+    }
+
+    fn next_geometry_id(&mut self) -> u32 {
+        self.inner.next_geometry_id()
+    }
+
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.cache_geometry(id, &vertices, indices);
+        self.inner.create_geometry(id, vertices, indices);
+    }
+
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        let bytes = vertices.as_bytes();
+        let cached = self.geometries.get_mut(&id).filter(|cached| {
+            cached.stride == vertices.stride()
+                && cached.vertices.len() == bytes.len()
+                && cached.indices.len() == indices.len()
+        });
+        let Some(cached) = cached else {
+            self.cache_geometry(id, &vertices, indices);
+            self.inner.update_geometry(id, vertices, indices);
+            return;
+        };
+
+        let changes = GeometryChanges {
+            vertices: changed_range(&cached.vertices, bytes),
+            indices: changed_range(as_bytes(&cached.indices), as_bytes(indices)),
+        };
+        if changes.is_empty() {
+            return;
+        }
+        cached.vertices.copy_from_slice(bytes);
+        cached.indices.copy_from_slice(indices);
+        self.inner
+            .update_geometry_ranges(id, vertices, indices, &changes);
+    }
+
+    fn destroy_geometry(&mut self, id: u32) {
+        self.geometries.remove(&id);
+        self.inner.destroy_geometry(id);
+    }
+
+    fn update_command_list(&mut self, commands: CommandList<'_>) {
+        self.inner.update_command_list(commands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_range_is_word_aligned() {
+        let old = [0u8; 16];
+        let mut new = old;
+        assert_eq!(changed_range(&old, &new), 0..0);
+
+        new[5] = 1;
+        assert_eq!(changed_range(&old, &new), 4..8);
+
+        new[13] = 1;
+        assert_eq!(changed_range(&old, &new), 4..16);
+    }
+
+    #[test]
+    fn changed_range_clamps_partial_word() {
+        let old = [0u8; 10];
+        let mut new = old;
+        new[9] = 1;
+        assert_eq!(changed_range(&old, &new), 8..10);
+
+        new[0] = 1;
+        assert_eq!(changed_range(&old, &new), 0..10);
+    }
+
+    #[test]
+    fn changed_rect_bounds_changed_pixels() {
+        // 4x3 pixels, 2 bytes each.
+        let old = [0u8; 24];
+        let mut new = old;
+        assert_eq!(changed_rect(&old, &new, 4, 2), IntRect::default());
+
+        // Second byte of pixel (1, 0) and first byte of pixel (2, 2).
+        new[3] = 1;
+        new[20] = 1;
+        assert_eq!(
+            changed_rect(&old, &new, 4, 2),
+            IntRect {
+                left: 1,
+                top: 0,
+                right: 3,
+                bottom: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn changed_rect_single_pixel() {
+        let old = [0u8; 16];
+        let mut new = old;
+        new[7] = 1;
+        assert_eq!(
+            changed_rect(&old, &new, 2, 4),
+            IntRect {
+                left: 1,
+                top: 0,
+                right: 2,
+                bottom: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use super::{with_gpu_driver, GpuDriver, RenderBufferId, TextureId};
+use crate::{sys::ULRenderTarget, BitmapFormat, Rect};
+
+/// Where a GPU accelerated view is rendered to, see `View::render_target`.
+///
+/// The texture can be larger than the view, only `uv` of it holds the view's content.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderTarget {
+    pub texture_id: TextureId,
+    pub render_buffer_id: RenderBufferId,
+    /// Size of the view in pixels.
+    pub width: u32,
+    pub height: u32,
+    pub texture_width: u32,
+    pub texture_height: u32,
+    pub format: BitmapFormat,
+    /// Normalized texture coordinates of the view's content.
+    pub uv: Rect,
+}
+
+impl RenderTarget {
+    /// `None` when the view isn't GPU accelerated, hasn't been rendered yet or its texture format
+    /// is unknown.
+    pub fn from_raw(target: ULRenderTarget) -> Option<Self> {
+        if target.is_empty {
+            return None;
+        }
+
+        Some(Self {
+            texture_id: target.texture_id,
+            render_buffer_id: target.render_buffer_id,
+            width: target.width,
+            height: target.height,
+            texture_width: target.texture_width,
+            texture_height: target.texture_height,
+            format: BitmapFormat::from_raw(target.texture_format)?,
+            uv: Rect::from(target.uv_coords),
+        })
+    }
+
+    /// Look the target up in the driver of the current thread, for example to get the texture
+    /// a `WgpuDriver` rendered the view into.
+    ///
+    /// Returns `None` when the driver isn't a `D`, is busy or doesn't know the texture.
+    pub fn resolve<D: GpuDriver + ResolveRenderTarget>(&self) -> Option<D::Output> {
+        with_gpu_driver::<D, _>(|driver| driver.resolve_render_target(self))?
+    }
+}
+
+/// Implemented by drivers that can hand out the texture of a `RenderTarget`.
+pub trait ResolveRenderTarget {
+    type Output;
+
+    fn resolve_render_target(&self, target: &RenderTarget) -> Option<Self::Output>;
+}
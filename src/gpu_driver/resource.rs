@@ -0,0 +1,313 @@
+const INDEX_BITS: u32 = 24;
+const INDEX_MASK: u32 = (1 << INDEX_BITS) - 1;
+const VACANT: u32 = u32::MAX;
+
+fn join(index: u32, generation: u8) -> u32 {
+    ((generation as u32) << INDEX_BITS) | index
+}
+
+fn split(id: u32) -> (usize, u8) {
+    ((id & INDEX_MASK) as usize, (id >> INDEX_BITS) as u8)
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Slot {
+    generation: u8,
+    live: bool,
+}
+
+/// Hands out resource ids for `GpuDriver::next_texture_id` and friends, reusing freed ids.
+///
+/// The low 24 bits of an id are a slot index, the high 8 bits the generation of the slot, which
+/// is bumped whenever its id is freed. A reused slot gets a different id, so a stale id left over
+/// from a destroyed resource never refers to the new one. Generations start at 1, so 0, which
+/// Ultralight uses for "no texture", is never handed out.
+#[derive(Debug, Default, Clone)]
+pub struct IdAllocator {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allocate(&mut self) -> u32 {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.live = true;
+            return join(index, slot.generation);
+        }
+
+        let index = self.slots.len() as u32;
+        assert!(index <= INDEX_MASK, "Ran out of resource ids");
+        self.slots.push(Slot {
+            generation: 1,
+            live: true,
+        });
+        join(index, 1)
+    }
+
+    /// Free `id` so its slot can be reused, returns whether it was allocated.
+    ///
+    /// Freeing an id twice or a stale id returns `false`.
+    pub fn free(&mut self, id: u32) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+
+        let (index, _) = split(id);
+        let slot = &mut self.slots[index];
+        slot.live = false;
+        slot.generation = slot.generation.checked_add(1).unwrap_or(1);
+        self.free.push(index as u32);
+        self.len -= 1;
+        true
+    }
+
+    /// Whether `id` is allocated and not freed yet.
+    pub fn contains(&self, id: u32) -> bool {
+        let (index, generation) = split(id);
+        self.slots
+            .get(index)
+            .is_some_and(|slot| slot.live && slot.generation == generation)
+    }
+
+    /// Whether `id` was handed out by this allocator once but has been freed since.
+    pub fn is_stale(&self, id: u32) -> bool {
+        let (index, generation) = split(id);
+        generation != 0 && index < self.slots.len() && !self.contains(id)
+    }
+
+    /// Number of allocated ids.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Resources of a driver by id, with ids from an embedded `IdAllocator`.
+///
+/// Values are stored densely, so iterating is as fast as iterating a `Vec`. Get an id with
+/// `next_id`, then `insert` the resource under it once Ultralight creates it. Ids that were
+/// removed are never found again, inserting under one is caught by a debug assertion.
+#[derive(Debug, Clone)]
+pub struct ResourceMap<T> {
+    ids: IdAllocator,
+    /// Index into `values` for every slot of `ids`, `VACANT` when it holds no value.
+    positions: Vec<u32>,
+    values: Vec<T>,
+    /// Id of every value, to fix up `positions` when a value is moved.
+    value_ids: Vec<u32>,
+}
+
+impl<T> Default for ResourceMap<T> {
+    fn default() -> Self {
+        Self {
+            ids: IdAllocator::default(),
+            positions: Vec::new(),
+            values: Vec::new(),
+            value_ids: Vec::new(),
+        }
+    }
+}
+
+impl<T> ResourceMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate an id for a resource that will be inserted later.
+    pub fn next_id(&mut self) -> u32 {
+        self.ids.allocate()
+    }
+
+    /// Store `value` under `id`, returning the value it replaces.
+    ///
+    /// `id` has to come from `next_id` and not be removed yet, other ids are ignored.
+    pub fn insert(&mut self, id: u32, value: T) -> Option<T> {
+        if !self.ids.contains(id) {
+            debug_assert!(false, "Inserted unallocated or stale resource id {:#x}", id);
+            return None;
+        }
+
+        let (index, _) = split(id);
+        if index >= self.positions.len() {
+            self.positions.resize(index + 1, VACANT);
+        }
+        match self.positions[index] {
+            VACANT => {
+                self.positions[index] = self.values.len() as u32;
+                self.values.push(value);
+                self.value_ids.push(id);
+                None
+            }
+            position => Some(std::mem::replace(
+                &mut self.values[position as usize],
+                value,
+            )),
+        }
+    }
+
+    /// Remove the resource and free its id, `None` when it was removed already.
+    pub fn remove(&mut self, id: u32) -> Option<T> {
+        if !self.ids.free(id) {
+            return None;
+        }
+
+        let (index, _) = split(id);
+        let position = std::mem::replace(self.positions.get_mut(index)?, VACANT);
+        if position == VACANT {
+            return None;
+        }
+
+        let value = self.values.swap_remove(position as usize);
+        self.value_ids.swap_remove(position as usize);
+        if let Some(&moved) = self.value_ids.get(position as usize) {
+            self.positions[split(moved).0] = position;
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.position(id).map(|position| &self.values[position])
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut T> {
+        self.position(id).map(|position| &mut self.values[position])
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Number of stored resources.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
+        self.value_ids.iter().copied().zip(&self.values)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (u32, &mut T)> {
+        self.value_ids.iter().copied().zip(&mut self.values)
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    fn position(&self, id: u32) -> Option<usize> {
+        if !self.ids.contains(id) {
+            return None;
+        }
+
+        match *self.positions.get(split(id).0)? {
+            VACANT => None,
+            position => Some(position as usize),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reused_slot_gets_new_generation() {
+        let mut ids = IdAllocator::new();
+        let first = ids.allocate();
+        assert_ne!(first, 0);
+        assert!(ids.free(first));
+
+        let second = ids.allocate();
+        assert_eq!(split(second).0, split(first).0);
+        assert_ne!(second, first);
+        assert!(ids.contains(second));
+        assert_eq!(ids.len(), 1);
+    }
+
+    #[test]
+    fn stale_ids_are_rejected() {
+        let mut ids = IdAllocator::new();
+        let id = ids.allocate();
+        ids.free(id);
+        let reused = ids.allocate();
+
+        assert!(ids.is_stale(id));
+        assert!(!ids.contains(id));
+        assert!(!ids.free(id));
+        assert!(!ids.is_stale(reused));
+        assert!(!ids.is_stale(0));
+    }
+
+    #[test]
+    fn generation_skips_zero_on_wrap() {
+        let mut ids = IdAllocator::new();
+        let mut id = ids.allocate();
+        for _ in 0..u8::MAX {
+            ids.free(id);
+            id = ids.allocate();
+        }
+        assert_eq!(split(id), (0, 1));
+    }
+
+    #[test]
+    fn remove_fixes_up_moved_value() {
+        let mut map = ResourceMap::new();
+        let a = map.next_id();
+        let b = map.next_id();
+        let c = map.next_id();
+        map.insert(a, "a");
+        map.insert(b, "b");
+        map.insert(c, "c");
+
+        assert_eq!(map.remove(a), Some("a"));
+        assert_eq!(map.get(c), Some(&"c"));
+        assert_eq!(map.get(b), Some(&"b"));
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.remove(c), Some("c"));
+        assert_eq!(map.get(b), Some(&"b"));
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(b, &"b")]);
+    }
+
+    #[test]
+    fn stale_ids_are_not_found() {
+        let mut map = ResourceMap::new();
+        let id = map.next_id();
+        map.insert(id, 1);
+        assert_eq!(map.remove(id), Some(1));
+
+        assert_eq!(map.remove(id), None);
+        assert_eq!(map.get(id), None);
+        assert_eq!(map.get_mut(id), None);
+        assert!(!map.contains(id));
+
+        let reused = map.next_id();
+        map.insert(reused, 2);
+        assert_eq!(map.get(id), None);
+        assert_eq!(map.get(reused), Some(&2));
+    }
+
+    #[test]
+    fn insert_replaces_value() {
+        let mut map = ResourceMap::new();
+        let id = map.next_id();
+        assert_eq!(map.insert(id, 1), None);
+        assert_eq!(map.insert(id, 2), Some(1));
+        assert_eq!(map.get(id), Some(&2));
+        assert_eq!(map.len(), 1);
+    }
+}
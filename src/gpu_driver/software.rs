@@ -0,0 +1,667 @@
+use std::ops::Range;
+
+mod shader;
+
+use shader::{saturate, Shader, Varyings, Vec2, Vec3, Vec4};
+
+use super::{
+    CommandList, GeometryId, GpuCommand, GpuDriver, GpuState, RenderBufferId, RenderTarget,
+    ResolveRenderTarget, ResourceMap, TextureId, Vertex2f4ub2f, Vertex2f4ub2f2f28f, VertexBuffer,
+};
+use crate::{sys::ULRenderBuffer, Bitmap, BitmapFormat, IntRect};
+
+/// Texture of the `SoftwareDriver`, stored as tightly packed premultiplied RGBA.
+///
+/// Like the GPU drivers no sRGB conversion is done, values are sampled and blended as stored.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SoftwareTexture {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl SoftwareTexture {
+    /// Transparent black texture, used for render targets.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Copy `bitmap`, `A8_UNORM` bitmaps have their alpha replicated into every channel.
+    ///
+    /// Bitmaps of an unknown format come out transparent.
+    pub fn from_bitmap(bitmap: &Bitmap) -> Self {
+        let format = match bitmap.format() {
+            Some(format) if !bitmap.is_empty() => format,
+            _ => return Self::new(bitmap.width(), bitmap.height()),
+        };
+
+        let pixels = bitmap.lock_pixels();
+        let pixels = match format {
+            BitmapFormat::A8_UNORM => pixels.rows().flatten().flat_map(|&a| [a; 4]).collect(),
+            BitmapFormat::BGRA8_UNORM_SRGB => pixels
+                .rows()
+                .flat_map(|row| row.chunks_exact(4))
+                .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+                .collect(),
+        };
+
+        Self {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Premultiplied RGBA pixels, `width * 4` bytes per row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    fn clear(&mut self) {
+        self.pixels.fill(0);
+    }
+
+    fn texel(&self, x: i32, y: i32) -> Vec4 {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        let i = (y * self.width as usize + x) * 4;
+        std::array::from_fn(|c| self.pixels[i + c] as f32 / 255.0)
+    }
+
+    /// Bilinear sample with clamp to edge addressing, like the sampler of the example driver.
+    fn sample(&self, uv: Vec2) -> Vec4 {
+        if self.width == 0 || self.height == 0 || uv[0].is_nan() || uv[1].is_nan() {
+            return [0.0; 4];
+        }
+
+        let x = uv[0] * self.width as f32 - 0.5;
+        let y = uv[1] * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+
+        let top = mix(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+        let bottom = mix(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+        mix(top, bottom, fy)
+    }
+
+    /// Write a premultiplied color, blending uses the same factors as the example driver.
+    fn write(&mut self, x: u32, y: u32, color: Vec4, blend: bool) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.pixels[i..i + 4];
+
+        let color = if blend {
+            let dest: Vec4 = std::array::from_fn(|c| pixel[c] as f32 / 255.0);
+            let src_alpha = saturate(color[3]);
+            [
+                color[0] + dest[0] * (1.0 - src_alpha),
+                color[1] + dest[1] * (1.0 - src_alpha),
+                color[2] + dest[2] * (1.0 - src_alpha),
+                src_alpha * (1.0 - dest[3]) + dest[3],
+            ]
+        } else {
+            color
+        };
+
+        for (dest, value) in pixel.iter_mut().zip(color) {
+            *dest = (saturate(value) * 255.0).round() as u8;
+        }
+    }
+}
+
+fn mix(a: Vec4, b: Vec4, t: f32) -> Vec4 {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+#[derive(Debug)]
+enum Vertices {
+    Path(Vec<Vertex2f4ub2f>),
+    Quad(Vec<Vertex2f4ub2f2f28f>),
+}
+
+impl Vertices {
+    fn len(&self) -> usize {
+        match self {
+            Self::Path(vertices) => vertices.len(),
+            Self::Quad(vertices) => vertices.len(),
+        }
+    }
+
+    /// Inputs of vertex `i` as the vertex shaders pass them on.
+    fn varyings(&self, i: usize) -> (Vec2, Varyings) {
+        let color = |c: [u8; 4]| c.map(|c| c as f32 / 255.0);
+
+        match self {
+            Self::Path(vertices) => {
+                let v = &vertices[i];
+                let varyings = Varyings {
+                    color: color(v.color),
+                    obj: v.obj,
+                    ..Default::default()
+                };
+                (v.pos, varyings)
+            }
+            Self::Quad(vertices) => {
+                let v = &vertices[i];
+                let varyings = Varyings {
+                    color: color(v.color),
+                    tex: v.tex,
+                    obj: v.obj,
+                    data: [
+                        v.data0, v.data1, v.data2, v.data3, v.data4, v.data5, v.data6,
+                    ],
+                };
+                (v.pos, varyings)
+            }
+        }
+    }
+}
+
+impl From<VertexBuffer<'_>> for Vertices {
+    fn from(vertices: VertexBuffer<'_>) -> Self {
+        match vertices {
+            VertexBuffer::Vertex2f4ub2f(v) => Self::Path(v.to_vec()),
+            VertexBuffer::Vertex2f4ub2f2f28f(v) => Self::Quad(v.to_vec()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Geometry {
+    vertices: Vertices,
+    indices: Vec<u32>,
+}
+
+/// Vertex after the vertex shader: position in pixels, `1 / w` and its outputs.
+struct ShadedVertex {
+    pos: Vec2,
+    inv_w: f32,
+    varyings: Varyings,
+}
+
+impl ShadedVertex {
+    /// Returns `None` for vertices behind the viewer, triangles aren't clipped against the near plane.
+    fn new(transform: &[f32; 16], pos: Vec2, varyings: Varyings) -> Option<Self> {
+        // `transform` maps to pixels, the projection `ulApplyProjection` adds would only be undone
+        // by the viewport transform. Column major, like the shaders read it.
+        let m = transform;
+        let [x, y] = pos;
+        let w = m[3] * x + m[7] * y + m[15];
+        if w <= f32::EPSILON {
+            return None;
+        }
+
+        Some(Self {
+            pos: [
+                (m[0] * x + m[4] * y + m[12]) / w,
+                (m[1] * x + m[5] * y + m[13]) / w,
+            ],
+            inv_w: 1.0 / w,
+            varyings,
+        })
+    }
+}
+
+/// Twice the signed area of `a`, `b`, `p`, positive when `p` is right of `a -> b` with y pointing down.
+fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Top-left fill rule, so pixels on an edge shared by two triangles are only drawn once.
+fn covers(w: f32, a: Vec2, b: Vec2) -> bool {
+    let is_top = a[1] == b[1] && b[0] > a[0];
+    let is_left = b[1] < a[1];
+    w > 0.0 || (w == 0.0 && (is_top || is_left))
+}
+
+struct Triangle<'a> {
+    v: [&'a ShadedVertex; 3],
+    area: f32,
+}
+
+impl<'a> Triangle<'a> {
+    fn new(v0: &'a ShadedVertex, v1: &'a ShadedVertex, v2: &'a ShadedVertex) -> Option<Self> {
+        let area = edge(v0.pos, v1.pos, v2.pos);
+        // Ultralight doesn't cull, flip the winding so the coverage test works for both orders.
+        let (v, area) = if area < 0.0 {
+            ([v0, v2, v1], -area)
+        } else {
+            ([v0, v1, v2], area)
+        };
+        (area > 0.0 && area.is_finite()).then_some(Self { v, area })
+    }
+
+    fn edges(&self, p: Vec2) -> Vec3 {
+        let [v0, v1, v2] = self.v.map(|v| v.pos);
+        [edge(v1, v2, p), edge(v2, v0, p), edge(v0, v1, p)]
+    }
+
+    fn covers(&self, p: Vec2) -> bool {
+        let [v0, v1, v2] = self.v.map(|v| v.pos);
+        let w = self.edges(p);
+        covers(w[0], v1, v2) && covers(w[1], v2, v0) && covers(w[2], v0, v1)
+    }
+
+    /// Perspective correct barycentric weights of `p`.
+    fn weights(&self, p: Vec2) -> Vec3 {
+        let w = self.edges(p);
+        let w: Vec3 = std::array::from_fn(|i| w[i] / self.area * self.v[i].inv_w);
+        let sum = w[0] + w[1] + w[2];
+        w.map(|w| w / sum)
+    }
+
+    fn varyings(&self, p: Vec2) -> Varyings {
+        Varyings::mix(self.v.map(|v| &v.varyings), self.weights(p))
+    }
+
+    fn obj(&self, p: Vec2) -> Vec2 {
+        let w = self.weights(p);
+        let [a, b, c] = self.v.map(|v| v.varyings.obj);
+        std::array::from_fn(|i| a[i] * w[0] + b[i] * w[1] + c[i] * w[2])
+    }
+
+    /// Pixels whose center could lie inside the triangle, limited to `bounds`.
+    fn pixels(&self, bounds: IntRect) -> impl Iterator<Item = (u32, u32)> {
+        let xs = self.v.map(|v| v.pos[0]);
+        let ys = self.v.map(|v| v.pos[1]);
+        let min = |v: Vec3| v[0].min(v[1]).min(v[2]).floor().max(i32::MIN as f32) as i32;
+        let max = |v: Vec3| v[0].max(v[1]).max(v[2]).ceil().min(i32::MAX as f32) as i32;
+
+        let left = min(xs).max(bounds.left);
+        let right = max(xs).min(bounds.right);
+        let top = min(ys).max(bounds.top);
+        let bottom = max(ys).min(bounds.bottom);
+
+        (top..bottom.max(top))
+            .flat_map(move |y| (left..right.max(left)).map(move |x| (x as u32, y as u32)))
+    }
+}
+
+/// Reference `GpuDriver` that executes command lists on the CPU.
+///
+/// It follows the stock Fill and FillPath shaders, including blending, scissor and clip masks,
+/// which makes it handy for tests, headless rendering and for checking what a GPU driver should
+/// output. Render targets can be read back with `render_buffer` or `texture` after `Renderer::render`.
+#[derive(Debug, Default)]
+pub struct SoftwareDriver {
+    textures: ResourceMap<SoftwareTexture>,
+    /// Render buffer to the texture it renders into.
+    render_buffers: ResourceMap<TextureId>,
+    geometries: ResourceMap<Geometry>,
+}
+
+impl SoftwareDriver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn texture(&self, id: TextureId) -> Option<&SoftwareTexture> {
+        self.textures.get(id)
+    }
+
+    /// Texture a render buffer draws into.
+    pub fn render_buffer(&self, id: RenderBufferId) -> Option<&SoftwareTexture> {
+        self.texture(*self.render_buffers.get(id)?)
+    }
+
+    fn clear_render_buffer(&mut self, id: RenderBufferId) {
+        if let Some(&texture_id) = self.render_buffers.get(id) {
+            if let Some(texture) = self.textures.get_mut(texture_id) {
+                texture.clear();
+            }
+        }
+    }
+
+    fn draw_geometry(&mut self, id: GeometryId, indices: Range<u32>, state: &GpuState) {
+        let target_id = match self.render_buffers.get(state.render_buffer_id) {
+            Some(&target_id) => target_id,
+            None => return,
+        };
+        // Take the target out so the draw can sample the other textures while writing to it.
+        let mut target = match self.textures.get_mut(target_id) {
+            Some(target) => std::mem::take(target),
+            None => return,
+        };
+
+        if let Some(geometry) = self.geometries.get(id) {
+            let shader = Shader {
+                state,
+                texture0: state.texture_1.and_then(|id| self.textures.get(id)),
+                texture1: state.texture_2.and_then(|id| self.textures.get(id)),
+            };
+            draw(&mut target, geometry, indices, &shader);
+        }
+
+        if let Some(slot) = self.textures.get_mut(target_id) {
+            *slot = target;
+        }
+    }
+}
+
+fn draw(target: &mut SoftwareTexture, geometry: &Geometry, indices: Range<u32>, shader: &Shader) {
+    let state = shader.state;
+    let mut bounds = IntRect {
+        left: 0,
+        top: 0,
+        right: target.width.min(state.viewport_width) as i32,
+        bottom: target.height.min(state.viewport_height) as i32,
+    };
+    if let Some(scissor) = state.scissor {
+        bounds = IntRect {
+            left: bounds.left.max(scissor.left),
+            top: bounds.top.max(scissor.top),
+            right: bounds.right.min(scissor.right),
+            bottom: bounds.bottom.min(scissor.bottom),
+        };
+    }
+    if bounds.is_empty() {
+        return;
+    }
+
+    let start = (indices.start as usize).min(geometry.indices.len());
+    let end = (indices.end as usize).clamp(start, geometry.indices.len());
+
+    for triangle in geometry.indices[start..end].chunks_exact(3) {
+        let vertices = triangle.iter().map(|&i| {
+            let i = i as usize;
+            if i >= geometry.vertices.len() {
+                return None;
+            }
+            let (pos, varyings) = geometry.vertices.varyings(i);
+            ShadedVertex::new(&state.transform, pos, varyings)
+        });
+        let vertices = match vertices.collect::<Option<Vec<_>>>() {
+            Some(vertices) => vertices,
+            None => continue,
+        };
+        let triangle = match Triangle::new(&vertices[0], &vertices[1], &vertices[2]) {
+            Some(triangle) => triangle,
+            None => continue,
+        };
+
+        for (x, y) in triangle.pixels(bounds) {
+            let p = [x as f32 + 0.5, y as f32 + 0.5];
+            if !triangle.covers(p) {
+                continue;
+            }
+
+            let varyings = triangle.varyings(p);
+            let color = match shader.color(&varyings) {
+                Some(color) => color,
+                None => continue,
+            };
+            let color = if state.clip().is_empty() {
+                color
+            } else {
+                let obj_dx = triangle.obj([p[0] + 1.0, p[1]]);
+                let obj_dy = triangle.obj([p[0], p[1] + 1.0]);
+                shader.apply_clip(varyings.obj, obj_dx, obj_dy, color)
+            };
+
+            target.write(x, y, color, state.enable_blend);
+        }
+    }
+}
+
+impl ResolveRenderTarget for SoftwareDriver {
+    /// Copy of the texture, the view's content is at `RenderTarget::uv`.
+    type Output = SoftwareTexture;
+
+    fn resolve_render_target(&self, target: &RenderTarget) -> Option<SoftwareTexture> {
+        self.texture(target.texture_id).cloned()
+    }
+}
+
+impl GpuDriver for SoftwareDriver {
+    fn next_texture_id(&mut self) -> u32 {
+        self.textures.next_id()
+    }
+
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        self.textures
+            .insert(id, SoftwareTexture::from_bitmap(bitmap));
+    }
+
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        self.textures
+            .insert(id, SoftwareTexture::from_bitmap(bitmap));
+    }
+
+    fn destroy_texture(&mut self, id: u32) {
+        self.textures.remove(id);
+    }
+
+    fn next_render_buffer_id(&mut self) -> u32 {
+        self.render_buffers.next_id()
+    }
+
+    fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+        self.render_buffers.insert(id, render_buffer.texture_id);
+    }
+
+    fn destroy_render_buffer(&mut self, id: u32) {
+        self.render_buffers.remove(id);
+    }
+
+    fn next_geometry_id(&mut self) -> u32 {
+        self.geometries.next_id()
+    }
+
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        let geometry = Geometry {
+            vertices: vertices.into(),
+            indices: indices.to_vec(),
+        };
+        self.geometries.insert(id, geometry);
+    }
+
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.create_geometry(id, vertices, indices);
+    }
+
+    fn destroy_geometry(&mut self, id: u32) {
+        self.geometries.remove(id);
+    }
+
+    fn update_command_list(&mut self, commands: CommandList<'_>) {
+        for command in commands {
+            match command {
+                GpuCommand::ClearRenderBuffer { render_buffer_id } => {
+                    self.clear_render_buffer(render_buffer_id)
+                }
+                GpuCommand::DrawGeometry {
+                    geometry_id,
+                    indices,
+                    state,
+                } => self.draw_geometry(geometry_id, indices, &state),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::{
+        ULCommand, ULCommandList, ULCommandType_kCommandType_ClearRenderBuffer,
+        ULCommandType_kCommandType_DrawGeometry, ULShaderType_kShaderType_Fill,
+        ULShaderType_kShaderType_FillPath,
+    };
+
+    const IDENTITY: [f32; 16] = [
+        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    ];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+
+    /// Driver with a 4x4 render buffer and its id.
+    fn driver() -> (SoftwareDriver, RenderBufferId) {
+        let mut driver = SoftwareDriver::new();
+        // Creating textures takes a `Bitmap`, which needs Ultralight.
+        let texture = driver.next_texture_id();
+        driver.textures.insert(texture, SoftwareTexture::new(4, 4));
+        let render_buffer = driver.next_render_buffer_id();
+        driver.create_render_buffer(
+            render_buffer,
+            ULRenderBuffer {
+                texture_id: texture,
+                width: 4,
+                height: 4,
+                ..Default::default()
+            },
+        );
+        (driver, render_buffer)
+    }
+
+    /// Solid quad covering `rect`, as two triangles.
+    fn quad(driver: &mut SoftwareDriver, rect: IntRect, color: [u8; 4]) -> GeometryId {
+        let [left, top, right, bottom] =
+            [rect.left, rect.top, rect.right, rect.bottom].map(|v| v as f32);
+        let vertices = [[left, top], [right, top], [right, bottom], [left, bottom]].map(|pos| {
+            Vertex2f4ub2f2f28f {
+                pos,
+                color,
+                ..Default::default()
+            }
+        });
+        let id = driver.next_geometry_id();
+        driver.create_geometry(
+            id,
+            VertexBuffer::Vertex2f4ub2f2f28f(&vertices),
+            &[0, 1, 2, 0, 2, 3],
+        );
+        id
+    }
+
+    fn draw(render_buffer: RenderBufferId, geometry_id: GeometryId) -> ULCommand {
+        let mut command = ULCommand {
+            command_type: ULCommandType_kCommandType_DrawGeometry as _,
+            geometry_id,
+            indices_count: 6,
+            ..Default::default()
+        };
+        let state = &mut command.gpu_state;
+        state.render_buffer_id = render_buffer;
+        state.viewport_width = 4;
+        state.viewport_height = 4;
+        state.transform.data = IDENTITY;
+        state.shader_type = ULShaderType_kShaderType_Fill as _;
+        command
+    }
+
+    fn submit(driver: &mut SoftwareDriver, mut commands: Vec<ULCommand>) {
+        let list = ULCommandList {
+            size: commands.len() as u32,
+            commands: commands.as_mut_ptr(),
+        };
+        driver.update_command_list(unsafe { CommandList::from_raw(list) });
+    }
+
+    /// 4x4 pixels that are `color` inside `rect` and `background` elsewhere.
+    fn pixels(rect: IntRect, color: [u8; 4], background: [u8; 4]) -> Vec<u8> {
+        (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let inside = x >= rect.left && x < rect.right && y >= rect.top && y < rect.bottom;
+                if inside {
+                    color
+                } else {
+                    background
+                }
+            })
+            .collect()
+    }
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> IntRect {
+        IntRect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn fill_solid_quad() {
+        let (mut driver, render_buffer) = driver();
+        let geometry = quad(&mut driver, rect(1, 1, 3, 3), RED);
+        submit(&mut driver, vec![draw(render_buffer, geometry)]);
+
+        assert_eq!(
+            driver.render_buffer(render_buffer).unwrap().pixels(),
+            pixels(rect(1, 1, 3, 3), RED, [0; 4])
+        );
+    }
+
+    #[test]
+    fn fill_path_quad() {
+        let (mut driver, render_buffer) = driver();
+        let vertices = [[0.0, 0.0], [2.0, 0.0], [2.0, 4.0], [0.0, 4.0]].map(|pos| Vertex2f4ub2f {
+            pos,
+            color: RED,
+            obj: [0.0; 2],
+        });
+        let geometry = driver.next_geometry_id();
+        driver.create_geometry(
+            geometry,
+            VertexBuffer::Vertex2f4ub2f(&vertices),
+            &[0, 1, 2, 0, 2, 3],
+        );
+        let mut command = draw(render_buffer, geometry);
+        command.gpu_state.shader_type = ULShaderType_kShaderType_FillPath as _;
+        submit(&mut driver, vec![command]);
+
+        assert_eq!(
+            driver.render_buffer(render_buffer).unwrap().pixels(),
+            pixels(rect(0, 0, 2, 4), RED, [0; 4])
+        );
+    }
+
+    #[test]
+    fn blend_and_scissor() {
+        let (mut driver, render_buffer) = driver();
+        let background = quad(&mut driver, rect(0, 0, 4, 4), RED);
+        let overlay = quad(&mut driver, rect(0, 0, 4, 4), [0, 128, 0, 128]);
+        let mut blended = draw(render_buffer, overlay);
+        blended.gpu_state.enable_blend = true;
+        blended.gpu_state.enable_scissor = true;
+        blended.gpu_state.scissor_rect.left = 2;
+        blended.gpu_state.scissor_rect.top = 0;
+        blended.gpu_state.scissor_rect.right = 4;
+        blended.gpu_state.scissor_rect.bottom = 2;
+        submit(&mut driver, vec![draw(render_buffer, background), blended]);
+
+        assert_eq!(
+            driver.render_buffer(render_buffer).unwrap().pixels(),
+            pixels(rect(2, 0, 4, 2), [127, 128, 0, 255], RED)
+        );
+    }
+
+    #[test]
+    fn clear_render_buffer() {
+        let (mut driver, render_buffer) = driver();
+        let geometry = quad(&mut driver, rect(0, 0, 4, 4), RED);
+        let mut clear = ULCommand {
+            command_type: ULCommandType_kCommandType_ClearRenderBuffer as _,
+            ..Default::default()
+        };
+        clear.gpu_state.render_buffer_id = render_buffer;
+        submit(&mut driver, vec![draw(render_buffer, geometry), clear]);
+
+        assert_eq!(
+            driver.render_buffer(render_buffer).unwrap().pixels(),
+            [0; 64]
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use super::SoftwareTexture;
+use crate::gpu_driver::{GpuState, ShaderType};
+
+pub(super) type Vec2 = [f32; 2];
+pub(super) type Vec3 = [f32; 3];
+pub(super) type Vec4 = [f32; 4];
+
+const FILL_SOLID: u32 = 0;
+const FILL_IMAGE: u32 = 1;
+const FILL_PATTERN_IMAGE: u32 = 2;
+const FILL_PATTERN_GRADIENT: u32 = 3;
+const FILL_ROUNDED_RECT: u32 = 7;
+const FILL_BOX_SHADOW: u32 = 8;
+const FILL_BLEND: u32 = 9;
+const FILL_MASK: u32 = 10;
+const FILL_GLYPH: u32 = 11;
+
+const AA_WIDTH: f32 = 0.354;
+
+/// Values interpolated across a triangle, the `VS_OUTPUT` of the stock shaders.
+#[derive(Debug, Default, Copy, Clone)]
+pub(super) struct Varyings {
+    pub color: Vec4,
+    pub tex: Vec2,
+    pub obj: Vec2,
+    pub data: [Vec4; 7],
+}
+
+impl Varyings {
+    /// Weighted sum of three vertices, `weights` should add up to one.
+    pub fn mix(v: [&Self; 3], weights: Vec3) -> Self {
+        fn mix<const N: usize>(a: [f32; N], b: [f32; N], c: [f32; N], w: Vec3) -> [f32; N] {
+            std::array::from_fn(|i| a[i] * w[0] + b[i] * w[1] + c[i] * w[2])
+        }
+
+        Self {
+            color: mix(v[0].color, v[1].color, v[2].color, weights),
+            tex: mix(v[0].tex, v[1].tex, v[2].tex, weights),
+            obj: mix(v[0].obj, v[1].obj, v[2].obj, weights),
+            data: std::array::from_fn(|i| mix(v[0].data[i], v[1].data[i], v[2].data[i], weights)),
+        }
+    }
+}
+
+/// CPU port of the pixel shaders in `examples/shaders`, bound for a single draw.
+///
+/// Function names follow `frag_fill.hlsl` so the two can be compared side by side.
+pub(super) struct Shader<'a> {
+    pub state: &'a GpuState,
+    pub texture0: Option<&'a SoftwareTexture>,
+    pub texture1: Option<&'a SoftwareTexture>,
+}
+
+impl Shader<'_> {
+    /// Premultiplied color of a pixel before clipping, `None` when it is discarded.
+    pub fn color(&self, v: &Varyings) -> Option<Vec4> {
+        match self.state.shader_type {
+            ShaderType::Fill => self.fill(v),
+            ShaderType::FillPath => Some(v.color),
+        }
+    }
+
+    /// `applyClip`, `obj_dx` and `obj_dy` are the object coordinates one pixel to the right and below,
+    /// they stand in for `fwidth`.
+    pub fn apply_clip(&self, obj: Vec2, obj_dx: Vec2, obj_dy: Vec2, mut color: Vec4) -> Vec4 {
+        for data in self.state.clip() {
+            let col = |i: usize| -> Vec4 {
+                [
+                    data[i * 4],
+                    data[i * 4 + 1],
+                    data[i * 4 + 2],
+                    data[i * 4 + 3],
+                ]
+            };
+            let origin = [col(0)[0], col(0)[1]];
+            let size = [col(0)[2], col(0)[3]];
+            let (radii_x, radii_y) = unpack(col(1));
+            let inverse = col(3)[2] != 0.0;
+
+            let distance = |p: Vec2| {
+                let p = transform_affine(
+                    p,
+                    [col(2)[0], col(2)[1]],
+                    [col(2)[2], col(2)[3]],
+                    [col(3)[0], col(3)[1]],
+                );
+                let d = sd_round_rect(sub(p, origin), size, radii_x, radii_y);
+                if inverse {
+                    -d
+                } else {
+                    d
+                }
+            };
+
+            let d = distance(obj);
+            let fwidth = (distance(obj_dx) - d).abs() + (distance(obj_dy) - d).abs();
+            let alpha = antialias2(-d, fwidth);
+            color = scale(color, alpha);
+        }
+        color
+    }
+
+    fn fill(&self, v: &Varyings) -> Option<Vec4> {
+        let color = match (v.data[0][0] + 0.5) as u32 {
+            FILL_SOLID => v.color,
+            FILL_IMAGE => self.fill_image(v),
+            FILL_PATTERN_IMAGE => self.fill_pattern_image(v),
+            FILL_PATTERN_GRADIENT => self.fill_pattern_gradient(v),
+            FILL_ROUNDED_RECT => fill_rounded_rect(v),
+            FILL_BOX_SHADOW => fill_box_shadow(v)?,
+            FILL_BLEND => self.fill_blend(v),
+            FILL_MASK => self.fill_mask(v),
+            FILL_GLYPH => self.fill_glyph(v),
+            _ => v.color,
+        };
+        Some(color)
+    }
+
+    fn scalar(&self, i: usize) -> f32 {
+        self.state.uniform_scalar[i]
+    }
+
+    fn vector(&self, i: usize) -> Vec4 {
+        self.state.uniform_vector[i]
+    }
+
+    fn fill_image(&self, v: &Varyings) -> Vec4 {
+        mul(sample(self.texture0, v.tex), v.color)
+    }
+
+    fn fill_pattern_image(&self, v: &Varyings) -> Vec4 {
+        let tile_rect_uv = self.vector(0);
+        let tile_size = [self.vector(1)[2], self.vector(1)[3]];
+        let a = [self.vector(2)[0], self.vector(2)[1]];
+        let b = [self.vector(2)[2], self.vector(2)[3]];
+        let c = [self.vector(3)[0], self.vector(3)[1]];
+
+        let transformed = transform_affine(v.obj, a, b, c);
+        let uv = [
+            frac(transformed[0] / tile_size[0]),
+            frac(transformed[1] / tile_size[1]),
+        ];
+        let uv = [
+            uv[0] * (tile_rect_uv[2] - tile_rect_uv[0]) + tile_rect_uv[0],
+            uv[1] * (tile_rect_uv[3] - tile_rect_uv[1]) + tile_rect_uv[1],
+        ];
+
+        mul(sample(self.texture0, uv), v.color)
+    }
+
+    /// Gradient stop `offset`, the first four live in the vertex data and the rest in the uniforms.
+    fn gradient_stop(&self, v: &Varyings, offset: usize) -> (f32, Vec4) {
+        if offset < 4 {
+            (v.data[2][offset], v.data[3 + offset])
+        } else {
+            (self.scalar(offset - 4), self.vector(offset - 4))
+        }
+    }
+
+    fn fill_pattern_gradient(&self, v: &Varyings) -> Vec4 {
+        let num_stops = (v.data[0][1] + 0.5) as usize;
+        let is_radial = (v.data[0][2] + 0.5) as u32 != 0;
+        let p0 = [v.data[1][0], v.data[1][1]];
+        let p1 = [v.data[1][2], v.data[1][3]];
+
+        let t = if is_radial {
+            let (r0, r1) = (p1[0], p1[1]);
+            let r_delta = r1 - r0;
+            saturate(length(sub(v.tex, p0)) / r_delta - r0 / r_delta)
+        } else {
+            let dir = sub(p1, p0);
+            saturate(dot(sub(v.tex, p0), dir) / dot(dir, dir))
+        };
+
+        let (mut previous, first) = self.gradient_stop(v, 0);
+        let mut color = first;
+        // The shader unrolls this for at most seven stops.
+        for i in 1..num_stops.clamp(2, 7) {
+            let (percent, stop_color) = self.gradient_stop(v, i);
+            color = lerp(color, stop_color, ramp(previous, percent, t));
+            previous = percent;
+        }
+        color
+    }
+
+    fn fill_blend(&self, v: &Varyings) -> Vec4 {
+        let src = self.fill_image(v);
+        let dest = sample(self.texture1, v.obj);
+        let (s, d) = (rgb(src), rgb(dest));
+        let separable = |col: Vec3| with_alpha(scale3(col, src[3]), dest[3] * src[3]);
+
+        match (v.data[0][1] + 0.5) as u32 {
+            0 => [0.0; 4],
+            1 => src,
+            2 => add(src, scale(dest, 1.0 - src[3])),
+            3 => scale(src, dest[3]),
+            4 => scale(src, 1.0 - dest[3]),
+            5 => add(scale(src, dest[3]), scale(dest, 1.0 - src[3])),
+            6 => add(scale(src, 1.0 - dest[3]), dest),
+            7 => scale(dest, src[3]),
+            8 => scale(dest, 1.0 - src[3]),
+            9 => add(scale(src, 1.0 - dest[3]), scale(dest, src[3])),
+            10 => add(scale(src, 1.0 - dest[3]), scale(dest, 1.0 - src[3])).map(saturate),
+            11 => separable(zip3(s, d, f32::min)),
+            12 => add(src, dest).map(saturate),
+            13 => separable(zip3(s, d, |s, d| (d - s).abs())),
+            14 => separable(zip3(s, d, |s, d| s * d)),
+            15 => separable(zip3(s, d, |s, d| 1.0 - (1.0 - d) * (1.0 - s))),
+            16 => separable(blend_overlay(s, d)),
+            17 => separable(zip3(s, d, f32::max)),
+            18 => separable(zip3(s, d, |s, d| {
+                if s == 1.0 {
+                    s
+                } else {
+                    (d / (1.0 - s)).min(1.0)
+                }
+            })),
+            19 => separable(zip3(s, d, |s, d| {
+                if s == 0.0 {
+                    s
+                } else {
+                    (1.0 - (1.0 - d) / s).max(0.0)
+                }
+            })),
+            20 => separable(blend_overlay(d, s)),
+            21 => separable(zip3(s, d, |s, d| {
+                if s < 0.5 {
+                    2.0 * d * s + d * d * (1.0 - 2.0 * s)
+                } else {
+                    d.sqrt() * (2.0 * s - 1.0) + 2.0 * d * (1.0 - s)
+                }
+            })),
+            22 => separable(zip3(s, d, |s, d| d + s - 2.0 * d * s)),
+            23 => {
+                let base = rgb_to_hsl(d);
+                separable(hsl_to_rgb([rgb_to_hsl(s)[0], base[1], base[2]]))
+            }
+            24 => {
+                let base = rgb_to_hsl(d);
+                separable(hsl_to_rgb([base[0], rgb_to_hsl(s)[1], base[2]]))
+            }
+            25 => {
+                let blend = rgb_to_hsl(s);
+                separable(hsl_to_rgb([blend[0], blend[1], rgb_to_hsl(d)[2]]))
+            }
+            26 => {
+                let base = rgb_to_hsl(d);
+                separable(hsl_to_rgb([base[0], base[1], rgb_to_hsl(s)[2]]))
+            }
+            _ => src,
+        }
+    }
+
+    fn fill_mask(&self, v: &Varyings) -> Vec4 {
+        let color = self.fill_image(v);
+        scale(color, sample(self.texture1, v.obj)[3])
+    }
+
+    fn fill_glyph(&self, v: &Varyings) -> Vec4 {
+        let alpha = sample(self.texture0, v.tex)[3] * v.color[3];
+        let fill_color_luma = v.data[0][1];
+        let corrected_alpha = sample(self.texture1, [alpha, fill_color_luma])[3];
+        with_alpha(scale3(rgb(v.color), corrected_alpha), corrected_alpha)
+    }
+}
+
+fn fill_rounded_rect(v: &Varyings) -> Vec4 {
+    let size = [v.data[0][2], v.data[0][3]];
+    let p = [(v.tex[0] - 0.5) * size[0], (v.tex[1] - 0.5) * size[1]];
+    let d = sd_round_rect(p, size, v.data[1], v.data[2]);
+
+    let alpha = antialias(-d, AA_WIDTH, 0.0) * v.color[3];
+    let mut color = with_alpha(scale3(rgb(v.color), alpha), alpha);
+
+    let stroke_width = v.data[3][0];
+    let stroke_color = v.data[4];
+    if stroke_width > 0.0 {
+        let alpha = inner_stroke(stroke_width, d) * stroke_color[3];
+        let stroke = with_alpha(scale3(rgb(stroke_color), alpha), alpha);
+        color = over(stroke, color);
+    }
+    color
+}
+
+fn fill_box_shadow(v: &Varyings) -> Option<Vec4> {
+    let p = v.obj;
+    let inset = (v.data[0][1] + 0.5) as u32 != 0;
+    let radius = v.data[0][2];
+    let origin = [v.data[1][0], v.data[1][1]];
+    let size = [v.data[1][2], v.data[1][3]];
+    let clip_origin = [v.data[4][0], v.data[4][1]];
+    let clip_size = [v.data[4][2], v.data[4][3]];
+
+    let sd_clip = sd_round_rect(sub(p, clip_origin), clip_size, v.data[5], v.data[6]);
+    let sd_rect = sd_round_rect(sub(p, origin), size, v.data[2], v.data[3]);
+
+    let (clip, d) = if inset {
+        (-sd_rect, -sd_clip)
+    } else {
+        (sd_clip, sd_rect)
+    };
+    if clip < 0.0 {
+        return None;
+    }
+
+    let alpha = if radius >= 1.0 {
+        antialias(-d, radius * 2.0 + 0.2, 0.0).powf(1.9) * 3.3 / (radius * 1.2).powf(0.15)
+    } else {
+        antialias(-d, AA_WIDTH, if inset { -1.0 } else { 1.0 })
+    };
+    let alpha = alpha.clamp(0.0, 1.0) * v.color[3];
+    Some(with_alpha(scale3(rgb(v.color), alpha), alpha))
+}
+
+/// Bilinear sample with clamp to edge, unbound textures read as transparent black.
+fn sample(texture: Option<&SoftwareTexture>, uv: Vec2) -> Vec4 {
+    match texture {
+        Some(texture) => texture.sample(uv),
+        None => [0.0; 4],
+    }
+}
+
+fn sd_rect(p: Vec2, size: Vec2) -> f32 {
+    let d = [p[0].abs() - size[0], p[1].abs() - size[1]];
+    d[0].max(d[1]).min(0.0) + length([d[0].max(0.0), d[1].max(0.0)])
+}
+
+// `sdEllipse` by Inigo Quilez, MIT licensed, see `examples/shaders/frag_fill.hlsl` for the full text.
+fn sd_ellipse(p: Vec2, ab: Vec2) -> f32 {
+    if (ab[0] - ab[1]).abs() < 0.1 {
+        return length(p) - ab[0];
+    }
+
+    let mut p = [p[0].abs(), p[1].abs()];
+    let mut ab = ab;
+    if p[0] > p[1] {
+        p = [p[1], p[0]];
+        ab = [ab[1], ab[0]];
+    }
+
+    let l = ab[1] * ab[1] - ab[0] * ab[0];
+
+    let m = ab[0] * p[0] / l;
+    let n = ab[1] * p[1] / l;
+    let m2 = m * m;
+    let n2 = n * n;
+
+    let c = (m2 + n2 - 1.0) / 3.0;
+    let c3 = c * c * c;
+
+    let q = c3 + m2 * n2 * 2.0;
+    let d = c3 + m2 * n2;
+    let g = m + m * n2;
+
+    let co = if d < 0.0 {
+        let h = (q / c3).acos() / 3.0;
+        let s = h.cos();
+        let t = h.sin() * 3.0f32.sqrt();
+        let rx = (-c * (s + t + 2.0) + m2).sqrt();
+        let ry = (-c * (s - t + 2.0) + m2).sqrt();
+        (ry + sign(l) * rx + g.abs() / (rx * ry) - m) / 2.0
+    } else {
+        let h = 2.0 * m * n * d.sqrt();
+        let s = sign(q + h) * (q + h).abs().powf(1.0 / 3.0);
+        let u = sign(q - h) * (q - h).abs().powf(1.0 / 3.0);
+        let rx = -s - u - c * 4.0 + 2.0 * m2;
+        let ry = (s - u) * 3.0f32.sqrt();
+        let rm = (rx * rx + ry * ry).sqrt();
+        let p = ry / (rm - rx).sqrt();
+        (p + 2.0 * g / rm - m) / 2.0
+    };
+
+    let si = (1.0 - co * co).sqrt();
+    let r = [ab[0] * co, ab[1] * si];
+    length(sub(r, p)) * sign(p[1] - r[1])
+}
+
+fn sd_round_rect(p: Vec2, size: Vec2, rx: Vec4, ry: Vec4) -> f32 {
+    let size = [size[0] * 0.5, size[1] * 0.5];
+
+    let corner = [-size[0] + rx[0], -size[1] + ry[0]]; // Top-Left
+    if rx[0] * ry[0] > 0.0 && p[0] < corner[0] && p[1] <= corner[1] {
+        return sd_ellipse(sub(p, corner), [rx[0], ry[0]]);
+    }
+
+    let corner = [size[0] - rx[1], -size[1] + ry[1]]; // Top-Right
+    if rx[1] * ry[1] > 0.0 && p[0] >= corner[0] && p[1] <= corner[1] {
+        return sd_ellipse(sub(p, corner), [rx[1], ry[1]]);
+    }
+
+    let corner = [size[0] - rx[2], size[1] - ry[2]]; // Bottom-Right
+    if rx[2] * ry[2] > 0.0 && p[0] >= corner[0] && p[1] >= corner[1] {
+        return sd_ellipse(sub(p, corner), [rx[2], ry[2]]);
+    }
+
+    let corner = [-size[0] + rx[3], size[1] - ry[3]]; // Bottom-Left
+    if rx[3] * ry[3] > 0.0 && p[0] < corner[0] && p[1] > corner[1] {
+        return sd_ellipse(sub(p, corner), [rx[3], ry[3]]);
+    }
+
+    sd_rect(p, size)
+}
+
+fn antialias(d: f32, width: f32, median: f32) -> f32 {
+    smoothstep(median - width, median + width, d)
+}
+
+fn antialias2(d: f32, fwidth: f32) -> f32 {
+    if fwidth == 0.0 {
+        return if d > 0.0 { 1.0 } else { 0.0 };
+    }
+    smoothstep(-0.6180469, 0.6180469, d / fwidth)
+}
+
+fn inner_stroke(stroke_width: f32, d: f32) -> f32 {
+    antialias(-d, AA_WIDTH, 0.0).min(1.0 - antialias(-d, AA_WIDTH, stroke_width))
+}
+
+fn unpack(x: Vec4) -> (Vec4, Vec4) {
+    const S: f32 = 65536.0;
+    let a = x.map(|x| (x / S).floor());
+    let b = std::array::from_fn(|i| (x[i] - a[i] * S).floor());
+    (a, b)
+}
+
+fn transform_affine(val: Vec2, a: Vec2, b: Vec2, c: Vec2) -> Vec2 {
+    [
+        val[0] * a[0] + val[1] * b[0] + c[0],
+        val[0] * a[1] + val[1] * b[1] + c[1],
+    ]
+}
+
+fn blend_overlay(src: Vec3, dest: Vec3) -> Vec3 {
+    zip3(src, dest, |s, d| {
+        if d < 0.5 {
+            2.0 * d * s
+        } else {
+            1.0 - 2.0 * (1.0 - d) * (1.0 - s)
+        }
+    })
+}
+
+fn rgb_to_hsl(col: Vec3) -> Vec3 {
+    const EPS: f32 = 0.0000001;
+    let [r, g, b] = col;
+    let min = r.min(g.min(b));
+    let max = r.max(g.max(b));
+    let step = |edge: f32, x: f32| if x >= edge { 1.0 } else { 0.0 };
+    let mask = [
+        step(g, r) * step(b, r),
+        step(r, g) * step(b, g),
+        step(r, b) * step(g, b),
+    ];
+    let h = [
+        mask[0] * (0.0 + (g - b) / (max - min + EPS)) / 6.0,
+        mask[1] * (2.0 + (b - r) / (max - min + EPS)) / 6.0,
+        mask[2] * (4.0 + (r - g) / (max - min + EPS)) / 6.0,
+    ];
+    [
+        frac(1.0 + h[0] + h[1] + h[2]),
+        (max - min) / (1.0 - (min + max - 1.0).abs() + EPS),
+        (min + max) * 0.5,
+    ]
+}
+
+fn hsl_to_rgb(c: Vec3) -> Vec3 {
+    [0.0, 4.0, 2.0].map(|offset: f32| {
+        let rgb = (((c[0] * 6.0 + offset) % 6.0 - 3.0).abs() - 1.0).clamp(0.0, 1.0);
+        c[2] + c[1] * (rgb - 0.5) * (1.0 - (2.0 * c[2] - 1.0).abs())
+    })
+}
+
+/// The shaders' `blend`, premultiplied source over destination.
+fn over(src: Vec4, dest: Vec4) -> Vec4 {
+    add(src, scale(dest, 1.0 - src[3]))
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = saturate((x - edge0) / (edge1 - edge0));
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn ramp(min: f32, max: f32, val: f32) -> f32 {
+    saturate((val - min) / (max - min))
+}
+
+/// Clamp to `0..=1`, NaN becomes 0 like on the GPU.
+pub(super) fn saturate(x: f32) -> f32 {
+    if x.is_nan() {
+        0.0
+    } else {
+        x.clamp(0.0, 1.0)
+    }
+}
+
+/// HLSL `sign`, returns 0 for 0.
+fn sign(x: f32) -> f32 {
+    if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+fn frac(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn lerp(a: Vec4, b: Vec4, t: f32) -> Vec4 {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn length(v: Vec2) -> f32 {
+    v[0].hypot(v[1])
+}
+
+fn dot(a: Vec2, b: Vec2) -> f32 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn sub(a: Vec2, b: Vec2) -> Vec2 {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn add(a: Vec4, b: Vec4) -> Vec4 {
+    std::array::from_fn(|i| a[i] + b[i])
+}
+
+fn mul(a: Vec4, b: Vec4) -> Vec4 {
+    std::array::from_fn(|i| a[i] * b[i])
+}
+
+fn scale(v: Vec4, s: f32) -> Vec4 {
+    v.map(|x| x * s)
+}
+
+fn scale3(v: Vec3, s: f32) -> Vec3 {
+    v.map(|x| x * s)
+}
+
+fn zip3(a: Vec3, b: Vec3, f: impl Fn(f32, f32) -> f32) -> Vec3 {
+    std::array::from_fn(|i| f(a[i], b[i]))
+}
+
+fn rgb(v: Vec4) -> Vec3 {
+    [v[0], v[1], v[2]]
+}
+
+fn with_alpha(rgb: Vec3, alpha: f32) -> Vec4 {
+    [rgb[0], rgb[1], rgb[2], alpha]
+}
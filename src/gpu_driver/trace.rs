@@ -0,0 +1,964 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    ops::Range,
+};
+
+use super::{
+    CommandList, GeometryChanges, GpuDriver, Vertex2f4ub2f, Vertex2f4ub2f2f28f, VertexBuffer,
+};
+use crate::{
+    sys::{ULCommand, ULCommandList, ULGPUState, ULIntRect, ULRenderBuffer},
+    Bitmap, BitmapFormat, IntRect,
+};
+
+/// First bytes of every trace file.
+const MAGIC: [u8; 8] = *b"ULGPUTRC";
+/// Version written by `RecordingDriver`, bumped whenever the encoding of a call changes.
+///
+/// Version 2 added partial texture and geometry updates, `Replay` still reads version 1.
+pub const TRACE_VERSION: u32 = 2;
+
+const BEGIN_SYNCHRONIZE: u8 = 0;
+const END_SYNCHRONIZE: u8 = 1;
+const NEXT_TEXTURE_ID: u8 = 2;
+const CREATE_TEXTURE: u8 = 3;
+const UPDATE_TEXTURE: u8 = 4;
+const DESTROY_TEXTURE: u8 = 5;
+const NEXT_RENDER_BUFFER_ID: u8 = 6;
+const CREATE_RENDER_BUFFER: u8 = 7;
+const DESTROY_RENDER_BUFFER: u8 = 8;
+const NEXT_GEOMETRY_ID: u8 = 9;
+const CREATE_GEOMETRY: u8 = 10;
+const UPDATE_GEOMETRY: u8 = 11;
+const DESTROY_GEOMETRY: u8 = 12;
+const UPDATE_COMMAND_LIST: u8 = 13;
+const UPDATE_TEXTURE_REGION: u8 = 14;
+const UPDATE_GEOMETRY_RANGES: u8 = 15;
+
+/// `GpuDriver` that forwards every call to `inner` and writes it to a trace.
+///
+/// The trace is a little endian binary stream: a header with `TRACE_VERSION`, followed by one
+/// record per driver call including bitmap, vertex and command payloads. Feed it back into any
+/// driver with `Replay`. Calls are recorded before they are forwarded, calls returning an id
+/// right after. Wrap files in a `BufWriter`, the trace is written in small pieces and flushed
+/// after every call.
+///
+/// Write errors stop the recording but never the inner driver, they are returned by `finish`.
+pub struct RecordingDriver<D, W: Write> {
+    inner: D,
+    encoder: Encoder<W>,
+    error: Option<io::Error>,
+}
+
+impl<D: GpuDriver, W: Write> RecordingDriver<D, W> {
+    /// Wrap `inner` and write the trace header to `writer`.
+    pub fn new(inner: D, writer: W) -> io::Result<Self> {
+        let mut encoder = Encoder(writer);
+        encoder.bytes(&MAGIC)?;
+        encoder.u32(TRACE_VERSION)?;
+
+        Ok(Self {
+            inner,
+            encoder,
+            error: None,
+        })
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// First write error, recording stopped when it happened.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Flush the trace and hand back the driver and writer.
+    pub fn finish(mut self) -> io::Result<(D, W)> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.encoder.0.flush()?;
+        Ok((self.inner, self.encoder.0))
+    }
+
+    /// Encode a call and flush it, before it is forwarded to `inner`.
+    ///
+    /// Flushing every call makes sure a trace holds the call that crashed the driver it captures.
+    fn record(&mut self, f: impl FnOnce(&mut Encoder<W>) -> io::Result<()>) {
+        if self.error.is_none() {
+            if let Err(error) = f(&mut self.encoder).and_then(|_| self.encoder.0.flush()) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+impl<D: GpuDriver, W: Write + 'static> GpuDriver for RecordingDriver<D, W> {
+    fn begin_synchronize(&mut self) {
+        self.record(|e| e.u8(BEGIN_SYNCHRONIZE));
+        self.inner.begin_synchronize();
+    }
+
+    fn end_synchronize(&mut self) {
+        self.record(|e| e.u8(END_SYNCHRONIZE));
+        self.inner.end_synchronize();
+    }
+
+    fn next_texture_id(&mut self) -> u32 {
+        let id = self.inner.next_texture_id();
+        self.record(|e| e.id(NEXT_TEXTURE_ID, id));
+        id
+    }
+
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        self.record(|e| {
+            e.id(CREATE_TEXTURE, id)?;
+            e.bitmap(bitmap)
+        });
+        self.inner.create_texture(id, bitmap);
+    }
+
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        self.record(|e| {
+            e.id(UPDATE_TEXTURE, id)?;
+            e.bitmap(bitmap)
+        });
+        self.inner.update_texture(id, bitmap);
+    }
+
+    fn update_texture_region(&mut self, id: u32, bitmap: &Bitmap, region: IntRect) {
+        self.record(|e| {
+            e.id(UPDATE_TEXTURE_REGION, id)?;
+            e.bitmap(bitmap)?;
+            e.rect(region)
+        });
+        self.inner.update_texture_region(id, bitmap, region);
+    }
+
+    fn destroy_texture(&mut self, id: u32) {
+        self.record(|e| e.id(DESTROY_TEXTURE, id));
+        self.inner.destroy_texture(id);
+    }
+
+    fn next_render_buffer_id(&mut self) -> u32 {
+        let id = self.inner.next_render_buffer_id();
+        self.record(|e| e.id(NEXT_RENDER_BUFFER_ID, id));
+        id
+    }
+
+    fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+        self.record(|e| {
+            e.id(CREATE_RENDER_BUFFER, id)?;
+            e.u32(render_buffer.texture_id)?;
+            e.u32(render_buffer.width)?;
+            e.u32(render_buffer.height)?;
+            e.bool(render_buffer.has_stencil_buffer)?;
+            e.bool(render_buffer.has_depth_buffer)
+        });
+        self.inner.create_render_buffer(id, render_buffer);
+    }
+
+    fn destroy_render_buffer(&mut self, id: u32) {
+        self.record(|e| e.id(DESTROY_RENDER_BUFFER, id));
+        self.inner.destroy_render_buffer(id);
+    }
+
+    fn next_geometry_id(&mut self) -> u32 {
+        let id = self.inner.next_geometry_id();
+        self.record(|e| e.id(NEXT_GEOMETRY_ID, id));
+        id
+    }
+
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.record(|e| {
+            e.id(CREATE_GEOMETRY, id)?;
+            e.geometry(vertices, indices)
+        });
+        self.inner.create_geometry(id, vertices, indices);
+    }
+
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.record(|e| {
+            e.id(UPDATE_GEOMETRY, id)?;
+            e.geometry(vertices, indices)
+        });
+        self.inner.update_geometry(id, vertices, indices);
+    }
+
+    fn update_geometry_ranges(
+        &mut self,
+        id: u32,
+        vertices: VertexBuffer<'_>,
+        indices: &[u32],
+        changes: &GeometryChanges,
+    ) {
+        self.record(|e| {
+            e.id(UPDATE_GEOMETRY_RANGES, id)?;
+            e.geometry(vertices, indices)?;
+            e.range(&changes.vertices)?;
+            e.range(&changes.indices)
+        });
+        self.inner
+            .update_geometry_ranges(id, vertices, indices, changes);
+    }
+
+    fn destroy_geometry(&mut self, id: u32) {
+        self.record(|e| e.id(DESTROY_GEOMETRY, id));
+        self.inner.destroy_geometry(id);
+    }
+
+    fn update_command_list(&mut self, commands: CommandList<'_>) {
+        self.record(|e| {
+            e.u8(UPDATE_COMMAND_LIST)?;
+            e.u32(commands.len() as u32)?;
+            commands
+                .raw()
+                .iter()
+                .try_for_each(|command| e.command(command))
+        });
+        self.inner.update_command_list(commands);
+    }
+}
+
+/// Plays a trace written by `RecordingDriver` into a driver.
+///
+/// Ids are mapped to the ones handed out by the driver being replayed into, so any driver works.
+/// Render target bitmaps are replayed as empty bitmaps of the same size.
+pub struct Replay<R> {
+    decoder: Decoder<R>,
+    version: u32,
+    textures: HashMap<u32, u32>,
+    render_buffers: HashMap<u32, u32>,
+    geometries: HashMap<u32, u32>,
+}
+
+impl<R: Read> Replay<R> {
+    /// Read and check the trace header.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut decoder = Decoder(reader);
+        let mut magic = [0; MAGIC.len()];
+        decoder.0.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("Not an Ultralight GPU trace"));
+        }
+
+        let version = decoder.u32()?;
+        if version == 0 || version > TRACE_VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported trace version {}",
+                version
+            )));
+        }
+
+        Ok(Self {
+            decoder,
+            version,
+            textures: HashMap::new(),
+            render_buffers: HashMap::new(),
+            geometries: HashMap::new(),
+        })
+    }
+
+    /// Version of the trace being replayed.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Replay a single call, returns `false` once the trace is over.
+    pub fn step(&mut self, driver: &mut dyn GpuDriver) -> io::Result<bool> {
+        let tag = match self.decoder.tag()? {
+            Some(tag) => tag,
+            None => return Ok(false),
+        };
+
+        match tag {
+            BEGIN_SYNCHRONIZE => driver.begin_synchronize(),
+            END_SYNCHRONIZE => driver.end_synchronize(),
+            NEXT_TEXTURE_ID => {
+                let id = self.decoder.u32()?;
+                self.textures.insert(id, driver.next_texture_id());
+            }
+            CREATE_TEXTURE => {
+                let id = map(&self.textures, self.decoder.u32()?);
+                driver.create_texture(id, &self.decoder.bitmap()?);
+            }
+            UPDATE_TEXTURE => {
+                let id = map(&self.textures, self.decoder.u32()?);
+                driver.update_texture(id, &self.decoder.bitmap()?);
+            }
+            UPDATE_TEXTURE_REGION => {
+                let id = map(&self.textures, self.decoder.u32()?);
+                let bitmap = self.decoder.bitmap()?;
+                driver.update_texture_region(id, &bitmap, self.decoder.rect()?);
+            }
+            DESTROY_TEXTURE => {
+                let id = self.decoder.u32()?;
+                driver.destroy_texture(map(&self.textures, id));
+                self.textures.remove(&id);
+            }
+            NEXT_RENDER_BUFFER_ID => {
+                let id = self.decoder.u32()?;
+                self.render_buffers
+                    .insert(id, driver.next_render_buffer_id());
+            }
+            CREATE_RENDER_BUFFER => {
+                let id = map(&self.render_buffers, self.decoder.u32()?);
+                let render_buffer = ULRenderBuffer {
+                    texture_id: map(&self.textures, self.decoder.u32()?),
+                    width: self.decoder.u32()?,
+                    height: self.decoder.u32()?,
+                    has_stencil_buffer: self.decoder.bool()?,
+                    has_depth_buffer: self.decoder.bool()?,
+                };
+                driver.create_render_buffer(id, render_buffer);
+            }
+            DESTROY_RENDER_BUFFER => {
+                let id = self.decoder.u32()?;
+                driver.destroy_render_buffer(map(&self.render_buffers, id));
+                self.render_buffers.remove(&id);
+            }
+            NEXT_GEOMETRY_ID => {
+                let id = self.decoder.u32()?;
+                self.geometries.insert(id, driver.next_geometry_id());
+            }
+            CREATE_GEOMETRY | UPDATE_GEOMETRY | UPDATE_GEOMETRY_RANGES => {
+                let id = map(&self.geometries, self.decoder.u32()?);
+                let (vertices, indices) = self.decoder.geometry()?;
+                let vertices = match &vertices {
+                    Vertices::Path(v) => VertexBuffer::Vertex2f4ub2f(v),
+                    Vertices::Quad(v) => VertexBuffer::Vertex2f4ub2f2f28f(v),
+                };
+                match tag {
+                    CREATE_GEOMETRY => driver.create_geometry(id, vertices, &indices),
+                    UPDATE_GEOMETRY => driver.update_geometry(id, vertices, &indices),
+                    _ => {
+                        let changes = GeometryChanges {
+                            vertices: self.decoder.range()?,
+                            indices: self.decoder.range()?,
+                        };
+                        driver.update_geometry_ranges(id, vertices, &indices, &changes);
+                    }
+                }
+            }
+            DESTROY_GEOMETRY => {
+                let id = self.decoder.u32()?;
+                driver.destroy_geometry(map(&self.geometries, id));
+                self.geometries.remove(&id);
+            }
+            UPDATE_COMMAND_LIST => {
+                let len = self.decoder.u32()?;
+                let mut commands = (0..len)
+                    .map(|_| {
+                        let mut command = self.decoder.command()?;
+                        let state = &mut command.gpu_state;
+                        command.geometry_id = map(&self.geometries, command.geometry_id);
+                        state.render_buffer_id = map(&self.render_buffers, state.render_buffer_id);
+                        state.texture_1_id = map(&self.textures, state.texture_1_id);
+                        state.texture_2_id = map(&self.textures, state.texture_2_id);
+                        state.texture_3_id = map(&self.textures, state.texture_3_id);
+                        Ok(command)
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+
+                let list = ULCommandList {
+                    size: len,
+                    commands: commands.as_mut_ptr(),
+                };
+                driver.update_command_list(unsafe { CommandList::from_raw(list) });
+            }
+            tag => return Err(invalid_data(format!("Unknown trace record {}", tag))),
+        }
+
+        Ok(true)
+    }
+
+    /// Replay the rest of the trace.
+    pub fn run(&mut self, driver: &mut dyn GpuDriver) -> io::Result<()> {
+        while self.step(driver)? {}
+        Ok(())
+    }
+}
+
+/// Replay a whole trace into `driver`.
+pub fn replay(reader: impl Read, driver: &mut dyn GpuDriver) -> io::Result<()> {
+    Replay::new(reader)?.run(driver)
+}
+
+/// Id the replayed driver uses for `id`, ids that never came from `next_*_id` are kept as is.
+fn map(ids: &HashMap<u32, u32>, id: u32) -> u32 {
+    ids.get(&id).copied().unwrap_or(id)
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+enum Vertices {
+    Path(Vec<Vertex2f4ub2f>),
+    Quad(Vec<Vertex2f4ub2f2f28f>),
+}
+
+struct Encoder<W>(W);
+
+impl<W: Write> Encoder<W> {
+    fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.0.write_all(bytes)
+    }
+
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.bytes(&[value])
+    }
+
+    fn bool(&mut self, value: bool) -> io::Result<()> {
+        self.u8(value as u8)
+    }
+
+    fn u32(&mut self, value: u32) -> io::Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn i32(&mut self, value: i32) -> io::Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn f32s(&mut self, values: &[f32]) -> io::Result<()> {
+        values.iter().try_for_each(|v| self.bytes(&v.to_le_bytes()))
+    }
+
+    fn id(&mut self, tag: u8, id: u32) -> io::Result<()> {
+        self.u8(tag)?;
+        self.u32(id)
+    }
+
+    fn rect(&mut self, rect: IntRect) -> io::Result<()> {
+        for edge in [rect.left, rect.top, rect.right, rect.bottom] {
+            self.i32(edge)?;
+        }
+        Ok(())
+    }
+
+    fn range(&mut self, range: &Range<usize>) -> io::Result<()> {
+        self.u32(range.start as u32)?;
+        self.u32(range.end as u32)
+    }
+
+    fn bitmap(&mut self, bitmap: &Bitmap) -> io::Result<()> {
+        self.u32(bitmap.width())?;
+        self.u32(bitmap.height())?;
+        self.u8(match bitmap.format() {
+            Some(BitmapFormat::A8_UNORM) => 0,
+            Some(BitmapFormat::BGRA8_UNORM_SRGB) => 1,
+            None => return Err(invalid_data("Can't record a bitmap of unknown format")),
+        })?;
+        // Render targets have no pixels.
+        self.bool(bitmap.is_empty())?;
+        if !bitmap.is_empty() {
+            // Rows are written without padding.
+            let pixels = bitmap.lock_pixels();
+            pixels.rows().try_for_each(|row| self.bytes(row))?;
+        }
+        Ok(())
+    }
+
+    fn geometry(&mut self, vertices: VertexBuffer<'_>, indices: &[u32]) -> io::Result<()> {
+        match vertices {
+            VertexBuffer::Vertex2f4ub2f(vertices) => {
+                self.u8(0)?;
+                self.u32(vertices.len() as u32)?;
+                for v in vertices {
+                    self.f32s(&v.pos)?;
+                    self.bytes(&v.color)?;
+                    self.f32s(&v.obj)?;
+                }
+            }
+            VertexBuffer::Vertex2f4ub2f2f28f(vertices) => {
+                self.u8(1)?;
+                self.u32(vertices.len() as u32)?;
+                for v in vertices {
+                    self.f32s(&v.pos)?;
+                    self.bytes(&v.color)?;
+                    self.f32s(&v.tex)?;
+                    self.f32s(&v.obj)?;
+                    for data in [
+                        v.data0, v.data1, v.data2, v.data3, v.data4, v.data5, v.data6,
+                    ] {
+                        self.f32s(&data)?;
+                    }
+                }
+            }
+        }
+
+        self.u32(indices.len() as u32)?;
+        indices.iter().try_for_each(|&i| self.u32(i))
+    }
+
+    fn command(&mut self, command: &ULCommand) -> io::Result<()> {
+        let state: &ULGPUState = &command.gpu_state;
+        self.u8(command.command_type)?;
+        self.u32(state.viewport_width)?;
+        self.u32(state.viewport_height)?;
+        self.f32s(&state.transform.data)?;
+        self.bool(state.enable_texturing)?;
+        self.bool(state.enable_blend)?;
+        self.u8(state.shader_type)?;
+        self.u32(state.render_buffer_id)?;
+        self.u32(state.texture_1_id)?;
+        self.u32(state.texture_2_id)?;
+        self.u32(state.texture_3_id)?;
+        self.f32s(&state.uniform_scalar)?;
+        for vector in &state.uniform_vector {
+            self.f32s(&vector.value)?;
+        }
+        // Unused clip matrices are left out.
+        let clip_size = state.clip_size.min(state.clip.len() as u8);
+        self.u8(clip_size)?;
+        for clip in &state.clip[..clip_size as usize] {
+            self.f32s(&clip.data)?;
+        }
+        self.bool(state.enable_scissor)?;
+        let rect = state.scissor_rect;
+        for edge in [rect.left, rect.top, rect.right, rect.bottom] {
+            self.i32(edge)?;
+        }
+        self.u32(command.geometry_id)?;
+        self.u32(command.indices_count)?;
+        self.u32(command.indices_offset)
+    }
+}
+
+struct Decoder<R>(R);
+
+impl<R: Read> Decoder<R> {
+    /// Tag of the next record, `None` at the end of the trace.
+    fn tag(&mut self) -> io::Result<Option<u8>> {
+        let mut tag = [0];
+        loop {
+            match self.0.read(&mut tag) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(tag[0])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.0.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn vec(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (&mut self.0).take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> io::Result<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    fn f32s<const N: usize>(&mut self) -> io::Result<[f32; N]> {
+        let mut values = [0.0; N];
+        for value in &mut values {
+            *value = f32::from_le_bytes(self.array()?);
+        }
+        Ok(values)
+    }
+
+    fn rect(&mut self) -> io::Result<IntRect> {
+        Ok(IntRect {
+            left: self.i32()?,
+            top: self.i32()?,
+            right: self.i32()?,
+            bottom: self.i32()?,
+        })
+    }
+
+    fn range(&mut self) -> io::Result<Range<usize>> {
+        Ok(self.u32()? as usize..self.u32()? as usize)
+    }
+
+    fn bitmap(&mut self) -> io::Result<Bitmap> {
+        let width = self.u32()?;
+        let height = self.u32()?;
+        let format = match self.u8()? {
+            0 => BitmapFormat::A8_UNORM,
+            1 => BitmapFormat::BGRA8_UNORM_SRGB,
+            format => return Err(invalid_data(format!("Unknown bitmap format {}", format))),
+        };
+        let is_render_target = self.bool()?;
+        if is_render_target {
+            return Ok(Bitmap::render_target(width, height, format));
+        }
+
+        let too_large = || invalid_data(format!("Bitmap of {}x{} is too large", width, height));
+        let row_bytes = width
+            .checked_mul(format.bytes_per_pixel())
+            .ok_or_else(too_large)?;
+        let size = (row_bytes as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(too_large)?;
+        let pixels = self.vec(size)?;
+        Bitmap::from_pixels(width, height, format, row_bytes, &pixels)
+            .map_err(|error| invalid_data(error.to_string()))
+    }
+
+    fn geometry(&mut self) -> io::Result<(Vertices, Vec<u32>)> {
+        let format = self.u8()?;
+        let len = self.u32()?;
+        let vertices = match format {
+            0 => Vertices::Path(
+                (0..len)
+                    .map(|_| {
+                        Ok(Vertex2f4ub2f {
+                            pos: self.f32s()?,
+                            color: self.array()?,
+                            obj: self.f32s()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            1 => Vertices::Quad(
+                (0..len)
+                    .map(|_| {
+                        Ok(Vertex2f4ub2f2f28f {
+                            pos: self.f32s()?,
+                            color: self.array()?,
+                            tex: self.f32s()?,
+                            obj: self.f32s()?,
+                            data0: self.f32s()?,
+                            data1: self.f32s()?,
+                            data2: self.f32s()?,
+                            data3: self.f32s()?,
+                            data4: self.f32s()?,
+                            data5: self.f32s()?,
+                            data6: self.f32s()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            format => return Err(invalid_data(format!("Unknown vertex format {}", format))),
+        };
+
+        let len = self.u32()?;
+        let indices = (0..len).map(|_| self.u32()).collect::<io::Result<_>>()?;
+        Ok((vertices, indices))
+    }
+
+    fn command(&mut self) -> io::Result<ULCommand> {
+        let command_type = self.u8()?;
+        let mut state = ULGPUState {
+            viewport_width: self.u32()?,
+            viewport_height: self.u32()?,
+            ..Default::default()
+        };
+        state.transform.data = self.f32s()?;
+        state.enable_texturing = self.bool()?;
+        state.enable_blend = self.bool()?;
+        state.shader_type = self.u8()?;
+        state.render_buffer_id = self.u32()?;
+        state.texture_1_id = self.u32()?;
+        state.texture_2_id = self.u32()?;
+        state.texture_3_id = self.u32()?;
+        state.uniform_scalar = self.f32s()?;
+        for vector in &mut state.uniform_vector {
+            vector.value = self.f32s()?;
+        }
+        state.clip_size = self.u8()?;
+        if state.clip_size as usize > state.clip.len() {
+            return Err(invalid_data(format!("Too many clips: {}", state.clip_size)));
+        }
+        for clip in &mut state.clip[..state.clip_size as usize] {
+            clip.data = self.f32s()?;
+        }
+        state.enable_scissor = self.bool()?;
+        state.scissor_rect = ULIntRect {
+            left: self.i32()?,
+            top: self.i32()?,
+            right: self.i32()?,
+            bottom: self.i32()?,
+        };
+
+        Ok(ULCommand {
+            command_type,
+            gpu_state: state,
+            geometry_id: self.u32()?,
+            indices_count: self.u32()?,
+            indices_offset: self.u32()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gpu_driver::GpuCommand,
+        sys::{
+            ULCommandType_kCommandType_ClearRenderBuffer, ULCommandType_kCommandType_DrawGeometry,
+        },
+    };
+
+    /// Logs the calls it gets, handing out ids from `next_id` up.
+    struct LogDriver {
+        next_id: u32,
+        calls: Vec<String>,
+    }
+
+    impl LogDriver {
+        fn new(next_id: u32) -> Self {
+            Self {
+                next_id,
+                calls: Vec::new(),
+            }
+        }
+
+        fn id(&mut self) -> u32 {
+            self.next_id += 1;
+            self.next_id
+        }
+    }
+
+    impl GpuDriver for LogDriver {
+        fn begin_synchronize(&mut self) {
+            self.calls.push("begin".to_owned());
+        }
+
+        fn end_synchronize(&mut self) {
+            self.calls.push("end".to_owned());
+        }
+
+        fn next_texture_id(&mut self) -> u32 {
+            self.id()
+        }
+
+        fn create_texture(&mut self, id: u32, _: &Bitmap) {
+            self.calls.push(format!("create_texture {id}"));
+        }
+
+        fn update_texture(&mut self, id: u32, _: &Bitmap) {
+            self.calls.push(format!("update_texture {id}"));
+        }
+
+        fn destroy_texture(&mut self, id: u32) {
+            self.calls.push(format!("destroy_texture {id}"));
+        }
+
+        fn next_render_buffer_id(&mut self) -> u32 {
+            self.id()
+        }
+
+        fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+            self.calls.push(format!(
+                "create_render_buffer {id} texture {} {}x{}",
+                render_buffer.texture_id, render_buffer.width, render_buffer.height
+            ));
+        }
+
+        fn destroy_render_buffer(&mut self, id: u32) {
+            self.calls.push(format!("destroy_render_buffer {id}"));
+        }
+
+        fn next_geometry_id(&mut self) -> u32 {
+            self.id()
+        }
+
+        fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+            self.calls
+                .push(format!("create_geometry {id} {vertices:?} {indices:?}"));
+        }
+
+        fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+            self.calls
+                .push(format!("update_geometry {id} {vertices:?} {indices:?}"));
+        }
+
+        fn update_geometry_ranges(
+            &mut self,
+            id: u32,
+            _: VertexBuffer<'_>,
+            indices: &[u32],
+            changes: &GeometryChanges,
+        ) {
+            self.calls.push(format!(
+                "update_geometry_ranges {id} {indices:?} {:?} {:?}",
+                changes.vertices, changes.indices
+            ));
+        }
+
+        fn destroy_geometry(&mut self, id: u32) {
+            self.calls.push(format!("destroy_geometry {id}"));
+        }
+
+        fn update_command_list(&mut self, commands: CommandList<'_>) {
+            for command in commands {
+                self.calls.push(match command {
+                    GpuCommand::ClearRenderBuffer { render_buffer_id } => {
+                        format!("clear {render_buffer_id}")
+                    }
+                    GpuCommand::DrawGeometry {
+                        geometry_id,
+                        indices,
+                        state,
+                    } => format!(
+                        "draw {geometry_id} {indices:?} into {} with {:?}",
+                        state.render_buffer_id, state.texture_1
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Drive `driver` like Ultralight would for a single frame.
+    fn frame(driver: &mut dyn GpuDriver) {
+        driver.begin_synchronize();
+        let texture = driver.next_texture_id();
+        let render_buffer = driver.next_render_buffer_id();
+        driver.create_render_buffer(
+            render_buffer,
+            ULRenderBuffer {
+                texture_id: texture,
+                width: 4,
+                height: 2,
+                ..Default::default()
+            },
+        );
+        let geometry = driver.next_geometry_id();
+        let vertices = [Vertex2f4ub2f {
+            pos: [1.0, 2.0],
+            color: [255, 0, 0, 255],
+            obj: [0.5, 0.5],
+        }];
+        driver.create_geometry(geometry, VertexBuffer::Vertex2f4ub2f(&vertices), &[0, 0, 0]);
+
+        let mut commands = [ULCommand::default(); 2];
+        commands[0].command_type = ULCommandType_kCommandType_ClearRenderBuffer as _;
+        commands[0].gpu_state.render_buffer_id = render_buffer;
+        commands[1].command_type = ULCommandType_kCommandType_DrawGeometry as _;
+        commands[1].geometry_id = geometry;
+        commands[1].indices_count = 3;
+        commands[1].gpu_state.render_buffer_id = render_buffer;
+        commands[1].gpu_state.texture_1_id = texture;
+        let list = ULCommandList {
+            size: commands.len() as u32,
+            commands: commands.as_mut_ptr(),
+        };
+        driver.update_command_list(unsafe { CommandList::from_raw(list) });
+        driver.end_synchronize();
+
+        driver.destroy_geometry(geometry);
+        driver.destroy_render_buffer(render_buffer);
+        driver.destroy_texture(texture);
+    }
+
+    fn expected_calls(texture: u32, render_buffer: u32, geometry: u32) -> Vec<String> {
+        let vertices = "Vertex2f4ub2f([Vertex2f4ub2f { pos: [1.0, 2.0], color: [255, 0, 0, 255], \
+                        obj: [0.5, 0.5] }])";
+        vec![
+            "begin".to_owned(),
+            format!("create_render_buffer {render_buffer} texture {texture} 4x2"),
+            format!("create_geometry {geometry} {vertices} [0, 0, 0]"),
+            format!("clear {render_buffer}"),
+            format!("draw {geometry} 0..3 into {render_buffer} with Some({texture})"),
+            "end".to_owned(),
+            format!("destroy_geometry {geometry}"),
+            format!("destroy_render_buffer {render_buffer}"),
+            format!("destroy_texture {texture}"),
+        ]
+    }
+
+    #[test]
+    fn replay_maps_ids() {
+        let mut recorder = RecordingDriver::new(LogDriver::new(0), Vec::new()).unwrap();
+        frame(&mut recorder);
+        let (recorded, trace) = recorder.finish().unwrap();
+        assert_eq!(recorded.calls, expected_calls(1, 2, 3));
+
+        let mut replayed = LogDriver::new(100);
+        let mut replay = Replay::new(&trace[..]).unwrap();
+        assert_eq!(replay.version(), TRACE_VERSION);
+        replay.run(&mut replayed).unwrap();
+        assert_eq!(replayed.calls, expected_calls(101, 102, 103));
+    }
+
+    #[test]
+    fn replay_keeps_partial_updates() {
+        let mut recorder = RecordingDriver::new(LogDriver::new(0), Vec::new()).unwrap();
+        let geometry = recorder.next_geometry_id();
+        let vertices = [Vertex2f4ub2f::default(); 3];
+        let changes = GeometryChanges {
+            vertices: 0..20,
+            indices: 4..8,
+        };
+        recorder.update_geometry_ranges(
+            geometry,
+            VertexBuffer::Vertex2f4ub2f(&vertices),
+            &[0, 1, 2],
+            &changes,
+        );
+        let (recorded, trace) = recorder.finish().unwrap();
+        assert_eq!(
+            recorded.calls,
+            ["update_geometry_ranges 1 [0, 1, 2] 0..20 4..8"]
+        );
+
+        let mut replayed = LogDriver::new(100);
+        replay(&trace[..], &mut replayed).unwrap();
+        assert_eq!(
+            replayed.calls,
+            ["update_geometry_ranges 101 [0, 1, 2] 0..20 4..8"]
+        );
+    }
+
+    #[test]
+    fn replay_reads_version_1() {
+        let mut trace = MAGIC.to_vec();
+        trace.extend_from_slice(&1u32.to_le_bytes());
+        trace.push(BEGIN_SYNCHRONIZE);
+
+        let mut replayed = LogDriver::new(0);
+        let mut replay = Replay::new(&trace[..]).unwrap();
+        assert_eq!(replay.version(), 1);
+        replay.run(&mut replayed).unwrap();
+        assert_eq!(replayed.calls, ["begin"]);
+    }
+
+    #[test]
+    fn replay_rejects_bad_traces() {
+        let error = Replay::new(&b"NOTATRACE"[..]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut trace = MAGIC.to_vec();
+        trace.extend_from_slice(&(TRACE_VERSION + 1).to_le_bytes());
+        let error = Replay::new(&trace[..]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut trace = MAGIC.to_vec();
+        trace.extend_from_slice(&TRACE_VERSION.to_le_bytes());
+        trace.push(u8::MAX);
+        let error = replay(&trace[..], &mut LogDriver::new(0)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,876 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::Range,
+};
+
+use super::{
+    CommandList, GeometryChanges, GeometryId, GpuCommand, GpuDriver, RenderBufferId, ShaderType,
+    TextureId, VertexBuffer,
+};
+use crate::{
+    sys::{ULCommand, ULCommandList, ULCommandType_kCommandType_DrawGeometry, ULRenderBuffer},
+    Bitmap, IntRect,
+};
+
+/// Kind of driver resource an id refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Texture,
+    RenderBuffer,
+    Geometry,
+}
+
+/// State the validation layer doesn't expect a driver to handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unsupported {
+    /// A render buffer asked for a depth or stencil buffer, Ultralight never draws with them.
+    DepthStencilBuffer { render_buffer_id: RenderBufferId },
+    /// Raw `ULCommand::command_type` that isn't a known `GpuCommand`.
+    CommandType(u8),
+    /// Raw `ULGPUState::shader_type` that isn't a known `ShaderType`.
+    ShaderType(u8),
+    /// The vertices of a geometry don't match the shader it is drawn with.
+    VertexFormat {
+        geometry_id: GeometryId,
+        shader_type: ShaderType,
+    },
+}
+
+/// Mistake caught by the `ValidationDriver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// An id was used after it was destroyed.
+    UseAfterDestroy {
+        kind: ResourceKind,
+        id: u32,
+    },
+    /// An id was used that was never created.
+    UnknownId {
+        kind: ResourceKind,
+        id: u32,
+    },
+    /// An id was created while it was still alive.
+    DoubleCreate {
+        kind: ResourceKind,
+        id: u32,
+    },
+    /// A resource was still alive when leaks were checked.
+    Leak {
+        kind: ResourceKind,
+        id: u32,
+    },
+    /// A draw samples the texture it renders into.
+    FeedbackLoop {
+        render_buffer_id: RenderBufferId,
+        texture_id: TextureId,
+    },
+    /// A draw uses indices past the end of the geometry.
+    IndicesOutOfRange {
+        geometry_id: GeometryId,
+        indices: Range<u32>,
+        index_count: u32,
+    },
+    /// A geometry has an index past its last vertex.
+    IndexOutOfRange {
+        geometry_id: GeometryId,
+        index: u32,
+        vertex_count: u32,
+    },
+    Unsupported(Unsupported),
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Texture => "texture",
+            Self::RenderBuffer => "render buffer",
+            Self::Geometry => "geometry",
+        })
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UseAfterDestroy { kind, id } => write!(f, "{} {} used after destroy", kind, id),
+            Self::UnknownId { kind, id } => write!(f, "{} {} was never created", kind, id),
+            Self::DoubleCreate { kind, id } => write!(f, "{} {} created twice", kind, id),
+            Self::Leak { kind, id } => write!(f, "{} {} was never destroyed", kind, id),
+            Self::FeedbackLoop {
+                render_buffer_id,
+                texture_id,
+            } => write!(
+                f,
+                "Draw into render buffer {} samples its own texture {}",
+                render_buffer_id, texture_id
+            ),
+            Self::IndicesOutOfRange {
+                geometry_id,
+                indices,
+                index_count,
+            } => write!(
+                f,
+                "Draw uses indices {:?} of geometry {} with {} indices",
+                indices, geometry_id, index_count
+            ),
+            Self::IndexOutOfRange {
+                geometry_id,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "Geometry {} has index {} but only {} vertices",
+                geometry_id, index, vertex_count
+            ),
+            Self::Unsupported(Unsupported::DepthStencilBuffer { render_buffer_id }) => write!(
+                f,
+                "Render buffer {} asks for a depth or stencil buffer",
+                render_buffer_id
+            ),
+            Self::Unsupported(Unsupported::CommandType(command_type)) => {
+                write!(f, "Unknown command type {}", command_type)
+            }
+            Self::Unsupported(Unsupported::ShaderType(shader_type)) => {
+                write!(f, "Unknown shader type {}", shader_type)
+            }
+            Self::Unsupported(Unsupported::VertexFormat {
+                geometry_id,
+                shader_type,
+            }) => write!(
+                f,
+                "Geometry {} can't be drawn with the {:?} shader",
+                geometry_id, shader_type
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Live and destroyed ids of one kind of resource.
+struct Tracker<T> {
+    kind: ResourceKind,
+    live: HashMap<u32, T>,
+    destroyed: HashSet<u32>,
+}
+
+impl<T> Tracker<T> {
+    fn new(kind: ResourceKind) -> Self {
+        Self {
+            kind,
+            live: HashMap::new(),
+            destroyed: HashSet::new(),
+        }
+    }
+
+    fn create(&mut self, id: u32, value: T) -> Result<(), ValidationError> {
+        if self.live.contains_key(&id) {
+            return Err(ValidationError::DoubleCreate {
+                kind: self.kind,
+                id,
+            });
+        }
+        // Ids may be handed out again once destroyed.
+        self.destroyed.remove(&id);
+        self.live.insert(id, value);
+        Ok(())
+    }
+
+    fn get(&self, id: u32) -> Result<&T, ValidationError> {
+        self.live.get(&id).ok_or_else(|| self.missing(id))
+    }
+
+    fn get_mut(&mut self, id: u32) -> Result<&mut T, ValidationError> {
+        let error = self.missing(id);
+        self.live.get_mut(&id).ok_or(error)
+    }
+
+    fn destroy(&mut self, id: u32) -> Result<T, ValidationError> {
+        let value = self.live.remove(&id).ok_or_else(|| self.missing(id))?;
+        self.destroyed.insert(id);
+        Ok(value)
+    }
+
+    fn missing(&self, id: u32) -> ValidationError {
+        if self.destroyed.contains(&id) {
+            ValidationError::UseAfterDestroy {
+                kind: self.kind,
+                id,
+            }
+        } else {
+            ValidationError::UnknownId {
+                kind: self.kind,
+                id,
+            }
+        }
+    }
+
+    fn leaks(&self) -> impl Iterator<Item = ValidationError> + '_ {
+        let mut ids: Vec<u32> = self.live.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| ValidationError::Leak {
+            kind: self.kind,
+            id,
+        })
+    }
+}
+
+struct GeometryInfo {
+    /// Shader the vertex format belongs to.
+    shader_type: ShaderType,
+    index_count: u32,
+}
+
+impl GeometryInfo {
+    fn new(vertices: &VertexBuffer<'_>, indices: &[u32]) -> Self {
+        let shader_type = match vertices {
+            VertexBuffer::Vertex2f4ub2f(_) => ShaderType::FillPath,
+            VertexBuffer::Vertex2f4ub2f2f28f(_) => ShaderType::Fill,
+        };
+
+        Self {
+            shader_type,
+            index_count: indices.len() as u32,
+        }
+    }
+}
+
+/// `GpuDriver` wrapper that checks every call before forwarding it to `inner`.
+///
+/// Errors go to the sink passed to `with_sink`, or are collected for `errors` otherwise. Calls
+/// that would make `inner` index a missing resource are dropped instead of forwarded, so a bug
+/// shows up as an error instead of a panic deep inside the driver.
+pub struct ValidationDriver<D> {
+    inner: D,
+    sink: Option<Box<dyn FnMut(ValidationError)>>,
+    errors: Vec<ValidationError>,
+    textures: Tracker<()>,
+    /// Render buffer to the texture it renders into.
+    render_buffers: Tracker<TextureId>,
+    geometries: Tracker<GeometryInfo>,
+}
+
+impl<D: GpuDriver> ValidationDriver<D> {
+    /// Validate calls to `inner`, collecting errors for `errors`.
+    pub fn new(inner: D) -> Self {
+        Self {
+            inner,
+            sink: None,
+            errors: Vec::new(),
+            textures: Tracker::new(ResourceKind::Texture),
+            render_buffers: Tracker::new(ResourceKind::RenderBuffer),
+            geometries: Tracker::new(ResourceKind::Geometry),
+        }
+    }
+
+    /// Validate calls to `inner`, passing every error to `sink` as soon as it is found.
+    pub fn with_sink(inner: D, sink: impl FnMut(ValidationError) + 'static) -> Self {
+        let mut driver = Self::new(inner);
+        driver.sink = Some(Box::new(sink));
+        driver
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Errors found so far, always empty when a sink is set.
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    pub fn take_errors(&mut self) -> Vec<ValidationError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Report every resource that is still alive as a leak.
+    ///
+    /// Call this once every `Renderer` using the driver has been dropped.
+    pub fn report_leaks(&mut self) {
+        let leaks: Vec<_> = self
+            .textures
+            .leaks()
+            .chain(self.render_buffers.leaks())
+            .chain(self.geometries.leaks())
+            .collect();
+        leaks.into_iter().for_each(|error| self.report(error));
+    }
+
+    fn report(&mut self, error: ValidationError) {
+        match &mut self.sink {
+            Some(sink) => sink(error),
+            None => self.errors.push(error),
+        }
+    }
+
+    /// Report `result`'s error, returns whether the call is fine to forward.
+    fn check<T>(&mut self, result: Result<T, ValidationError>) -> bool {
+        match result {
+            Ok(_) => true,
+            Err(error) => {
+                self.report(error);
+                false
+            }
+        }
+    }
+
+    fn check_indices(&mut self, id: GeometryId, vertices: &VertexBuffer<'_>, indices: &[u32]) {
+        let vertex_count = vertices.len() as u32;
+        if let Some(&index) = indices.iter().find(|&&i| i >= vertex_count) {
+            self.report(ValidationError::IndexOutOfRange {
+                geometry_id: id,
+                index,
+                vertex_count,
+            });
+        }
+    }
+
+    /// Errors of a single command, it is only forwarded when there are none.
+    fn command_errors(&self, raw: &ULCommand) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if raw.indices_offset.checked_add(raw.indices_count).is_none() {
+            errors.push(ValidationError::IndicesOutOfRange {
+                geometry_id: raw.geometry_id,
+                indices: raw.indices_offset..u32::MAX,
+                index_count: self
+                    .geometries
+                    .get(raw.geometry_id)
+                    .map_or(0, |geometry| geometry.index_count),
+            });
+            return errors;
+        }
+
+        let command = match GpuCommand::from_raw(raw) {
+            Some(command) => command,
+            // Overflowing ranges are caught above, so the shader type is unknown.
+            None if raw.command_type as i32 == ULCommandType_kCommandType_DrawGeometry => {
+                errors.push(ValidationError::Unsupported(Unsupported::ShaderType(
+                    raw.gpu_state.shader_type,
+                )));
+                return errors;
+            }
+            None => {
+                errors.push(ValidationError::Unsupported(Unsupported::CommandType(
+                    raw.command_type,
+                )));
+                return errors;
+            }
+        };
+
+        match command {
+            GpuCommand::ClearRenderBuffer { render_buffer_id } => {
+                ok(&mut errors, self.render_buffers.get(render_buffer_id));
+            }
+            GpuCommand::DrawGeometry {
+                geometry_id,
+                indices,
+                state,
+            } => {
+                let target =
+                    ok(&mut errors, self.render_buffers.get(state.render_buffer_id)).copied();
+                for texture_id in [state.texture_1, state.texture_2, state.texture_3]
+                    .into_iter()
+                    .flatten()
+                {
+                    ok(&mut errors, self.textures.get(texture_id));
+                    if target == Some(texture_id) {
+                        errors.push(ValidationError::FeedbackLoop {
+                            render_buffer_id: state.render_buffer_id,
+                            texture_id,
+                        });
+                    }
+                }
+
+                if let Some(geometry) = ok(&mut errors, self.geometries.get(geometry_id)) {
+                    if indices.end > geometry.index_count {
+                        errors.push(ValidationError::IndicesOutOfRange {
+                            geometry_id,
+                            indices,
+                            index_count: geometry.index_count,
+                        });
+                    }
+                    if geometry.shader_type != state.shader_type {
+                        errors.push(ValidationError::Unsupported(Unsupported::VertexFormat {
+                            geometry_id,
+                            shader_type: state.shader_type,
+                        }));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+impl<D: GpuDriver> GpuDriver for ValidationDriver<D> {
+    fn begin_synchronize(&mut self) {
+        self.inner.begin_synchronize();
+    }
+
+    fn end_synchronize(&mut self) {
+        self.inner.end_synchronize();
+    }
+
+    fn next_texture_id(&mut self) -> u32 {
+        self.inner.next_texture_id()
+    }
+
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        let result = self.textures.create(id, ());
+        if self.check(result) {
+            self.inner.create_texture(id, bitmap);
+        }
+    }
+
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        let result = self.textures.get(id).map(|_| ());
+        if self.check(result) {
+            self.inner.update_texture(id, bitmap);
+        }
+    }
+
+    fn update_texture_region(&mut self, id: u32, bitmap: &Bitmap, region: IntRect) {
+        let result = self.textures.get(id).map(|_| ());
+        if self.check(result) {
+            self.inner.update_texture_region(id, bitmap, region);
+        }
+    }
+
+    fn destroy_texture(&mut self, id: u32) {
+        let result = self.textures.destroy(id);
+        if self.check(result) {
+            self.inner.destroy_texture(id);
+        }
+    }
+
+    fn next_render_buffer_id(&mut self) -> u32 {
+        self.inner.next_render_buffer_id()
+    }
+
+    fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+        if render_buffer.has_depth_buffer || render_buffer.has_stencil_buffer {
+            self.report(ValidationError::Unsupported(
+                Unsupported::DepthStencilBuffer {
+                    render_buffer_id: id,
+                },
+            ));
+        }
+
+        let texture = self.textures.get(render_buffer.texture_id).map(|_| ());
+        let texture = self.check(texture);
+        let result = self.render_buffers.create(id, render_buffer.texture_id);
+        if self.check(result) && texture {
+            self.inner.create_render_buffer(id, render_buffer);
+        }
+    }
+
+    fn destroy_render_buffer(&mut self, id: u32) {
+        let result = self.render_buffers.destroy(id);
+        if self.check(result) {
+            self.inner.destroy_render_buffer(id);
+        }
+    }
+
+    fn next_geometry_id(&mut self) -> u32 {
+        self.inner.next_geometry_id()
+    }
+
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.check_indices(id, &vertices, indices);
+        let result = self
+            .geometries
+            .create(id, GeometryInfo::new(&vertices, indices));
+        if self.check(result) {
+            self.inner.create_geometry(id, vertices, indices);
+        }
+    }
+
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        self.check_indices(id, &vertices, indices);
+        let result = self
+            .geometries
+            .get_mut(id)
+            .map(|geometry| *geometry = GeometryInfo::new(&vertices, indices));
+        if self.check(result) {
+            self.inner.update_geometry(id, vertices, indices);
+        }
+    }
+
+    fn update_geometry_ranges(
+        &mut self,
+        id: u32,
+        vertices: VertexBuffer<'_>,
+        indices: &[u32],
+        changes: &GeometryChanges,
+    ) {
+        self.check_indices(id, &vertices, indices);
+        let result = self
+            .geometries
+            .get_mut(id)
+            .map(|geometry| *geometry = GeometryInfo::new(&vertices, indices));
+        if self.check(result) {
+            self.inner
+                .update_geometry_ranges(id, vertices, indices, changes);
+        }
+    }
+
+    fn destroy_geometry(&mut self, id: u32) {
+        let result = self.geometries.destroy(id);
+        if self.check(result) {
+            self.inner.destroy_geometry(id);
+        }
+    }
+
+    fn update_command_list(&mut self, commands: CommandList<'_>) {
+        let mut valid = Vec::with_capacity(commands.len());
+        for raw in commands.raw() {
+            let errors = self.command_errors(raw);
+            if errors.is_empty() {
+                valid.push(*raw);
+            }
+            errors.into_iter().for_each(|error| self.report(error));
+        }
+
+        if valid.len() == commands.len() {
+            self.inner.update_command_list(commands);
+        } else {
+            let list = ULCommandList {
+                size: valid.len() as u32,
+                commands: valid.as_mut_ptr(),
+            };
+            self.inner
+                .update_command_list(unsafe { CommandList::from_raw(list) });
+        }
+    }
+}
+
+/// Push the error of `result` to `errors`.
+fn ok<T>(errors: &mut Vec<ValidationError>, result: Result<T, ValidationError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(error) => {
+            errors.push(error);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gpu_driver::{Vertex2f4ub2f, Vertex2f4ub2f2f28f},
+        sys::{
+            ULCommandType_kCommandType_ClearRenderBuffer, ULCommandType_kCommandType_DrawGeometry,
+            ULShaderType_kShaderType_Fill, ULShaderType_kShaderType_FillPath,
+        },
+    };
+
+    /// Counts the commands forwarded to it.
+    #[derive(Default)]
+    struct CountDriver {
+        next_id: u32,
+        commands: usize,
+    }
+
+    impl GpuDriver for CountDriver {
+        fn next_texture_id(&mut self) -> u32 {
+            self.next_id += 1;
+            self.next_id
+        }
+
+        fn create_texture(&mut self, _: u32, _: &Bitmap) {}
+
+        fn update_texture(&mut self, _: u32, _: &Bitmap) {}
+
+        fn destroy_texture(&mut self, _: u32) {}
+
+        fn next_render_buffer_id(&mut self) -> u32 {
+            self.next_texture_id()
+        }
+
+        fn create_render_buffer(&mut self, _: u32, _: ULRenderBuffer) {}
+
+        fn destroy_render_buffer(&mut self, _: u32) {}
+
+        fn next_geometry_id(&mut self) -> u32 {
+            self.next_texture_id()
+        }
+
+        fn create_geometry(&mut self, _: u32, _: VertexBuffer<'_>, _: &[u32]) {}
+
+        fn update_geometry(&mut self, _: u32, _: VertexBuffer<'_>, _: &[u32]) {}
+
+        fn destroy_geometry(&mut self, _: u32) {}
+
+        fn update_command_list(&mut self, commands: CommandList<'_>) {
+            self.commands += commands.len();
+        }
+    }
+
+    const TEXTURE: TextureId = 1;
+    const RENDER_BUFFER: RenderBufferId = 2;
+    const GEOMETRY: GeometryId = 3;
+
+    /// Driver with a texture, a render buffer drawing into it and a 3 index path geometry.
+    fn driver() -> ValidationDriver<CountDriver> {
+        let mut driver = ValidationDriver::new(CountDriver::default());
+        // Creating textures takes a `Bitmap`, which needs Ultralight.
+        driver.textures.create(TEXTURE, ()).unwrap();
+        driver.create_render_buffer(RENDER_BUFFER, render_buffer(TEXTURE));
+        driver.create_geometry(
+            GEOMETRY,
+            VertexBuffer::Vertex2f4ub2f(&[Vertex2f4ub2f::default(); 3]),
+            &[0, 1, 2],
+        );
+        assert_eq!(driver.errors(), []);
+        driver
+    }
+
+    fn render_buffer(texture_id: TextureId) -> ULRenderBuffer {
+        ULRenderBuffer {
+            texture_id,
+            width: 4,
+            height: 4,
+            ..Default::default()
+        }
+    }
+
+    fn draw(indices: Range<u32>) -> ULCommand {
+        let mut command = ULCommand {
+            command_type: ULCommandType_kCommandType_DrawGeometry as _,
+            geometry_id: GEOMETRY,
+            indices_offset: indices.start,
+            indices_count: indices.end - indices.start,
+            ..Default::default()
+        };
+        command.gpu_state.render_buffer_id = RENDER_BUFFER;
+        command.gpu_state.shader_type = ULShaderType_kShaderType_FillPath as _;
+        command
+    }
+
+    /// Send `commands`, returns how many were forwarded.
+    fn submit(driver: &mut ValidationDriver<CountDriver>, mut commands: Vec<ULCommand>) -> usize {
+        let before = driver.inner().commands;
+        let list = ULCommandList {
+            size: commands.len() as u32,
+            commands: commands.as_mut_ptr(),
+        };
+        driver.update_command_list(unsafe { CommandList::from_raw(list) });
+        driver.inner().commands - before
+    }
+
+    #[test]
+    fn valid_calls_pass() {
+        let mut driver = driver();
+        let mut clear = draw(0..0);
+        clear.command_type = ULCommandType_kCommandType_ClearRenderBuffer as _;
+        assert_eq!(submit(&mut driver, vec![clear, draw(0..3)]), 2);
+
+        driver.destroy_geometry(GEOMETRY);
+        driver.destroy_render_buffer(RENDER_BUFFER);
+        driver.destroy_texture(TEXTURE);
+        driver.report_leaks();
+        assert_eq!(driver.errors(), []);
+    }
+
+    #[test]
+    fn use_after_destroy() {
+        let mut driver = driver();
+        driver.destroy_geometry(GEOMETRY);
+        assert_eq!(submit(&mut driver, vec![draw(0..3)]), 0);
+        driver.destroy_geometry(GEOMETRY);
+
+        let error = ValidationError::UseAfterDestroy {
+            kind: ResourceKind::Geometry,
+            id: GEOMETRY,
+        };
+        assert_eq!(driver.take_errors(), [error.clone(), error]);
+    }
+
+    #[test]
+    fn unknown_id() {
+        let mut driver = driver();
+        driver.destroy_texture(42);
+        driver.create_render_buffer(43, render_buffer(44));
+        assert_eq!(
+            driver.take_errors(),
+            [
+                ValidationError::UnknownId {
+                    kind: ResourceKind::Texture,
+                    id: 42,
+                },
+                ValidationError::UnknownId {
+                    kind: ResourceKind::Texture,
+                    id: 44,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn double_create() {
+        let mut driver = driver();
+        driver.create_render_buffer(RENDER_BUFFER, render_buffer(TEXTURE));
+        assert_eq!(
+            driver.take_errors(),
+            [ValidationError::DoubleCreate {
+                kind: ResourceKind::RenderBuffer,
+                id: RENDER_BUFFER,
+            }]
+        );
+    }
+
+    #[test]
+    fn leak() {
+        let mut driver = driver();
+        driver.destroy_render_buffer(RENDER_BUFFER);
+        driver.report_leaks();
+        assert_eq!(
+            driver.take_errors(),
+            [
+                ValidationError::Leak {
+                    kind: ResourceKind::Texture,
+                    id: TEXTURE,
+                },
+                ValidationError::Leak {
+                    kind: ResourceKind::Geometry,
+                    id: GEOMETRY,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn feedback_loop() {
+        let mut driver = driver();
+        let mut command = draw(0..3);
+        command.gpu_state.texture_1_id = TEXTURE;
+        assert_eq!(submit(&mut driver, vec![command]), 0);
+        assert_eq!(
+            driver.take_errors(),
+            [ValidationError::FeedbackLoop {
+                render_buffer_id: RENDER_BUFFER,
+                texture_id: TEXTURE,
+            }]
+        );
+    }
+
+    #[test]
+    fn indices_out_of_range() {
+        let mut driver = driver();
+        let mut overflow = draw(0..0);
+        overflow.indices_offset = 1;
+        overflow.indices_count = u32::MAX;
+        assert_eq!(submit(&mut driver, vec![draw(1..4), overflow]), 0);
+        assert_eq!(
+            driver.take_errors(),
+            [
+                ValidationError::IndicesOutOfRange {
+                    geometry_id: GEOMETRY,
+                    indices: 1..4,
+                    index_count: 3,
+                },
+                ValidationError::IndicesOutOfRange {
+                    geometry_id: GEOMETRY,
+                    indices: 1..u32::MAX,
+                    index_count: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        let mut driver = driver();
+        driver.update_geometry(
+            GEOMETRY,
+            VertexBuffer::Vertex2f4ub2f(&[Vertex2f4ub2f::default(); 2]),
+            &[0, 1, 2],
+        );
+        assert_eq!(
+            driver.take_errors(),
+            [ValidationError::IndexOutOfRange {
+                geometry_id: GEOMETRY,
+                index: 2,
+                vertex_count: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn unsupported_depth_stencil_buffer() {
+        let mut driver = driver();
+        let mut render_buffer = render_buffer(TEXTURE);
+        render_buffer.has_stencil_buffer = true;
+        driver.create_render_buffer(5, render_buffer);
+        assert_eq!(
+            driver.take_errors(),
+            [ValidationError::Unsupported(
+                Unsupported::DepthStencilBuffer {
+                    render_buffer_id: 5,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn unsupported_command_and_shader_type() {
+        let mut driver = driver();
+        let mut command_type = draw(0..3);
+        command_type.command_type = 99;
+        let mut shader_type = draw(0..3);
+        shader_type.gpu_state.shader_type = 99;
+        assert_eq!(submit(&mut driver, vec![command_type, shader_type]), 0);
+
+        assert_eq!(
+            driver.take_errors(),
+            [
+                ValidationError::Unsupported(Unsupported::CommandType(99)),
+                ValidationError::Unsupported(Unsupported::ShaderType(99)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_vertex_format() {
+        let mut driver = driver();
+        driver.update_geometry(
+            GEOMETRY,
+            VertexBuffer::Vertex2f4ub2f2f28f(&[Vertex2f4ub2f2f28f::default(); 3]),
+            &[0, 1, 2],
+        );
+        let mut command = draw(0..3);
+        assert_eq!(submit(&mut driver, vec![command]), 0);
+        command.gpu_state.shader_type = ULShaderType_kShaderType_Fill as _;
+        assert_eq!(submit(&mut driver, vec![command]), 1);
+
+        assert_eq!(
+            driver.take_errors(),
+            [ValidationError::Unsupported(Unsupported::VertexFormat {
+                geometry_id: GEOMETRY,
+                shader_type: ShaderType::FillPath,
+            })]
+        );
+    }
+
+    #[test]
+    fn sink_gets_errors() {
+        let errors = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = errors.clone();
+        let mut driver = ValidationDriver::with_sink(CountDriver::default(), move |error| {
+            sink.borrow_mut().push(error.to_string())
+        });
+        driver.destroy_geometry(7);
+
+        assert_eq!(driver.errors(), []);
+        assert_eq!(*errors.borrow(), ["geometry 7 was never created"]);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::sys::{
+    ULIndexBuffer, ULVertexBuffer, ULVertexBufferFormat_kVertexBufferFormat_2f_4ub_2f,
+    ULVertexBufferFormat_kVertexBufferFormat_2f_4ub_2f_2f_28f,
+};
+
+/// Path vertex, same layout as `ULVertex_2f_4ub_2f`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct Vertex2f4ub2f {
+    pub pos: [f32; 2],
+    pub color: [u8; 4],
+    pub obj: [f32; 2],
+}
+
+/// Quad vertex, same layout as `ULVertex_2f_4ub_2f_2f_28f`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct Vertex2f4ub2f2f28f {
+    pub pos: [f32; 2],
+    pub color: [u8; 4],
+    pub tex: [f32; 2],
+    pub obj: [f32; 2],
+    pub data0: [f32; 4],
+    pub data1: [f32; 4],
+    pub data2: [f32; 4],
+    pub data3: [f32; 4],
+    pub data4: [f32; 4],
+    pub data5: [f32; 4],
+    pub data6: [f32; 4],
+}
+
+// Our vertex types aren't packed, make sure that didn't introduce padding.
+const _: () = assert!(
+    std::mem::size_of::<Vertex2f4ub2f>() == std::mem::size_of::<crate::sys::ULVertex_2f_4ub_2f>()
+);
+const _: () = assert!(
+    std::mem::size_of::<Vertex2f4ub2f2f28f>()
+        == std::mem::size_of::<crate::sys::ULVertex_2f_4ub_2f_2f_28f>()
+);
+
+/// Format of a single vertex attribute.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VertexFormat {
+    Float32x2,
+    Float32x4,
+    /// Four bytes, used for vertex colors. The stock shaders divide by 255 themselves.
+    Uint8x4,
+}
+
+impl VertexFormat {
+    pub fn size(&self) -> u32 {
+        match self {
+            Self::Float32x2 => 8,
+            Self::Float32x4 => 16,
+            Self::Uint8x4 => 4,
+        }
+    }
+}
+
+/// Vertex attribute matching the inputs of the stock Ultralight shaders.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    pub format: VertexFormat,
+    /// Offset in bytes from the start of the vertex.
+    pub offset: u32,
+    pub shader_location: u32,
+}
+
+const fn attribute(format: VertexFormat, offset: u32, shader_location: u32) -> VertexAttribute {
+    VertexAttribute {
+        format,
+        offset,
+        shader_location,
+    }
+}
+
+impl Vertex2f4ub2f {
+    pub const STRIDE: u32 = std::mem::size_of::<Self>() as u32;
+    pub const ATTRIBUTES: [VertexAttribute; 3] = [
+        attribute(VertexFormat::Float32x2, 0, 0),  // pos
+        attribute(VertexFormat::Uint8x4, 8, 1),    // color
+        attribute(VertexFormat::Float32x2, 12, 2), // obj
+    ];
+}
+
+impl Vertex2f4ub2f2f28f {
+    pub const STRIDE: u32 = std::mem::size_of::<Self>() as u32;
+    pub const ATTRIBUTES: [VertexAttribute; 11] = [
+        attribute(VertexFormat::Float32x2, 0, 0),    // pos
+        attribute(VertexFormat::Uint8x4, 8, 1),      // color
+        attribute(VertexFormat::Float32x2, 12, 2),   // tex
+        attribute(VertexFormat::Float32x2, 20, 3),   // obj
+        attribute(VertexFormat::Float32x4, 28, 4),   // data0
+        attribute(VertexFormat::Float32x4, 44, 5),   // data1
+        attribute(VertexFormat::Float32x4, 60, 6),   // data2
+        attribute(VertexFormat::Float32x4, 76, 7),   // data3
+        attribute(VertexFormat::Float32x4, 92, 8),   // data4
+        attribute(VertexFormat::Float32x4, 108, 9),  // data5
+        attribute(VertexFormat::Float32x4, 124, 10), // data6
+    ];
+}
+
+/// Borrowed vertices of a geometry, only valid during the driver call it was passed to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VertexBuffer<'a> {
+    Vertex2f4ub2f(&'a [Vertex2f4ub2f]),
+    Vertex2f4ub2f2f28f(&'a [Vertex2f4ub2f2f28f]),
+}
+
+impl<'a> VertexBuffer<'a> {
+    /// # Safety
+    /// `vb.data` must point to `vb.size` valid bytes for the lifetime `'a`.
+    pub unsafe fn from_raw(vb: ULVertexBuffer) -> Self {
+        if vb.format == ULVertexBufferFormat_kVertexBufferFormat_2f_4ub_2f {
+            Self::Vertex2f4ub2f(cast_slice(vb.data, vb.size))
+        } else if vb.format == ULVertexBufferFormat_kVertexBufferFormat_2f_4ub_2f_2f_28f {
+            Self::Vertex2f4ub2f2f28f(cast_slice(vb.data, vb.size))
+        } else {
+            panic!("Unknown vertex buffer format {}", vb.format)
+        }
+    }
+
+    /// Number of vertices.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Vertex2f4ub2f(v) => v.len(),
+            Self::Vertex2f4ub2f2f28f(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size in bytes of a single vertex.
+    pub fn stride(&self) -> u32 {
+        match self {
+            Self::Vertex2f4ub2f(_) => Vertex2f4ub2f::STRIDE,
+            Self::Vertex2f4ub2f2f28f(_) => Vertex2f4ub2f2f28f::STRIDE,
+        }
+    }
+
+    pub fn attributes(&self) -> &'static [VertexAttribute] {
+        match self {
+            Self::Vertex2f4ub2f(_) => &Vertex2f4ub2f::ATTRIBUTES,
+            Self::Vertex2f4ub2f2f28f(_) => &Vertex2f4ub2f2f28f::ATTRIBUTES,
+        }
+    }
+
+    /// Raw bytes of the vertices, ready to be uploaded.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match *self {
+            Self::Vertex2f4ub2f(v) => as_bytes(v),
+            Self::Vertex2f4ub2f2f28f(v) => as_bytes(v),
+        }
+    }
+}
+
+/// Borrow the indices of a geometry.
+///
+/// # Safety
+/// `ib.data` must point to `ib.size` valid bytes for the lifetime `'a`.
+pub unsafe fn index_slice<'a>(ib: ULIndexBuffer) -> &'a [u32] {
+    cast_slice(ib.data, ib.size)
+}
+
+unsafe fn cast_slice<'a, T>(data: *const u8, size: u32) -> &'a [T] {
+    if data.is_null() || size == 0 {
+        return &[];
+    }
+
+    assert_eq!(
+        data as usize % std::mem::align_of::<T>(),
+        0,
+        "Ultralight buffer is not aligned"
+    );
+    std::slice::from_raw_parts(data as *const T, size as usize / std::mem::size_of::<T>())
+}
+
+pub(crate) fn as_bytes<T: Copy>(slice: &[T]) -> &[u8] {
+    // Vertex types are plain `repr(C)` floats and bytes without padding.
+    unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const u8, std::mem::size_of_val(slice)) }
+}
@@ -2,15 +2,23 @@ use crate::{
     sys::{
         ulViewLockJSContext, ulViewUnlockJSContext, JSContextGetGlobalObject, JSContextRef,
         JSEvaluateScript, JSObjectCallAsFunction, JSObjectGetProperty, JSObjectGetPropertyAtIndex,
-        JSObjectMake, JSObjectMakeArray, JSObjectMakeFunctionWithCallback,
+        JSObjectIsFunction, JSObjectMake, JSObjectMakeArray, JSObjectMakeFunctionWithCallback,
         JSObjectMakeTypedArrayWithArrayBuffer, JSObjectRef, JSObjectSetProperty,
-        JSStringCreateWithUTF8CString, JSStringRelease,
-        JSTypedArrayType_kJSTypedArrayTypeFloat32Array, JSValueIsArray, JSValueMakeNumber,
-        JSValueMakeString, JSValueRef, JSValueToNumber, JSValueToObject,
+        JSStringCreateWithUTF8CString, JSStringGetMaximumUTF8CStringSize, JSStringGetUTF8CString,
+        JSStringRef, JSStringRelease, JSType_kJSTypeBoolean, JSType_kJSTypeNull,
+        JSType_kJSTypeNumber, JSType_kJSTypeObject, JSType_kJSTypeString, JSType_kJSTypeUndefined,
+        JSTypedArrayType_kJSTypedArrayTypeFloat32Array, JSValueGetType, JSValueIsArray,
+        JSValueIsObject, JSValueIsUndefined, JSValueMakeNumber, JSValueMakeString, JSValueRef,
+        JSValueToBoolean, JSValueToNumber, JSValueToObject, JSValueToStringCopy,
     },
-    View,
+    ViewRef,
+};
+use std::{
+    ffi::CString,
+    fmt,
+    os::raw::c_void,
+    ptr::{null, null_mut},
 };
-use std::{ffi::CString, os::raw::c_void, ptr::null_mut};
 
 pub type RustCallback = dyn FnMut(&JSContext<'_>, &[JSValueRef]);
 
@@ -208,12 +216,12 @@ impl<const N: usize> IntoJSObject for [JSObjectRef; N] {
 }
 
 pub struct JSContext<'a> {
-    owner: Option<&'a View>,
+    owner: Option<&'a ViewRef<'a>>,
     inner: JSContextRef,
 }
 
 impl<'a> JSContext<'a> {
-    pub fn new(view: &'a View) -> Self {
+    pub fn new(view: &'a ViewRef<'a>) -> Self {
         let context = unsafe { ulViewLockJSContext(view.into()) };
         Self {
             owner: Some(view),
@@ -226,16 +234,54 @@ impl<'a> JSContext<'a> {
         JSObject::from_object(self, global_object)
     }
 
-    // TODO: Return option
+    /// Global function named `name`, `None` when there is none.
     // TODO: Wrap in wrapper so you can call functions directly?
-    pub fn get_function(&self, name: &str) -> JSObjectRef {
-        let name = CString::new(name).unwrap();
+    pub fn get_function(&self, name: &str) -> Option<JSObjectRef> {
+        let value = self.get_global_object().get_property(name);
 
         unsafe {
-            let name = JSStringCreateWithUTF8CString(name.as_ptr());
-            let func = JSEvaluateScript(self.inner, name, null_mut(), null_mut(), 0, null_mut());
-            JSStringRelease(name);
-            JSValueToObject(self.inner, func, null_mut())
+            if value.is_null() || !JSValueIsObject(self.inner, value) {
+                return None;
+            }
+            let object = JSValueToObject(self.inner, value, null_mut());
+            JSObjectIsFunction(self.inner, object).then_some(object)
+        }
+    }
+
+    /// Evaluate `script` in the global scope.
+    pub fn evaluate(&self, script: &str) -> Result<JSValue<'_>, JSException> {
+        self.evaluate_with_source(script, None, 1)
+    }
+
+    /// Evaluate `script` in the global scope, as if it was loaded from `source_url` starting at
+    /// `starting_line`. Both show up in exceptions and stack traces.
+    pub fn evaluate_with_source(
+        &self,
+        script: &str,
+        source_url: Option<&str>,
+        starting_line: i32,
+    ) -> Result<JSValue<'_>, JSException> {
+        unsafe {
+            let script = create_js_string(script);
+            let source_url = source_url.map_or(null_mut(), create_js_string);
+            let mut exception = null();
+            let value = JSEvaluateScript(
+                self.inner,
+                script,
+                null_mut(),
+                source_url,
+                starting_line,
+                &mut exception,
+            );
+            JSStringRelease(script);
+            if !source_url.is_null() {
+                JSStringRelease(source_url);
+            }
+
+            if !exception.is_null() {
+                return Err(JSException::from_value(self.inner, exception));
+            }
+            Ok(JSValue::from_value(self, value))
         }
     }
 
@@ -280,6 +326,148 @@ impl From<&JSContext<'_>> for JSContextRef {
     }
 }
 
+/* ========================= */
+/*          JSValue          */
+/* ========================= */
+
+/// A JavaScript value, converted to Rust where possible.
+pub enum JSValue<'a> {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    /// Objects, including arrays and functions.
+    Object(JSObject<'a>),
+    Symbol,
+}
+
+impl<'a> JSValue<'a> {
+    fn from_value(ctx: &'a JSContext<'a>, value: JSValueRef) -> Self {
+        let inner = ctx.inner;
+        let value_type = unsafe { JSValueGetType(inner, value) };
+
+        if value_type == JSType_kJSTypeUndefined {
+            Self::Undefined
+        } else if value_type == JSType_kJSTypeNull {
+            Self::Null
+        } else if value_type == JSType_kJSTypeBoolean {
+            Self::Boolean(unsafe { JSValueToBoolean(inner, value) })
+        } else if value_type == JSType_kJSTypeNumber {
+            Self::Number(unsafe { JSValueToNumber(inner, value, null_mut()) })
+        } else if value_type == JSType_kJSTypeString {
+            Self::String(unsafe { value_to_string(inner, value) })
+        } else if value_type == JSType_kJSTypeObject {
+            Self::Object(JSObject::from_value(ctx, value))
+        } else {
+            Self::Symbol
+        }
+    }
+}
+
+/// Exception thrown by `JSContext::evaluate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSException {
+    /// The exception converted to a string, like `TypeError: undefined is not a function`.
+    pub message: String,
+    pub line: Option<u32>,
+    pub source_url: Option<String>,
+    pub stack: Option<String>,
+}
+
+impl JSException {
+    fn from_value(ctx: JSContextRef, value: JSValueRef) -> Self {
+        unsafe {
+            let message = value_to_string(ctx, value);
+            if !JSValueIsObject(ctx, value) {
+                return Self {
+                    message,
+                    line: None,
+                    source_url: None,
+                    stack: None,
+                };
+            }
+
+            let object = JSValueToObject(ctx, value, null_mut());
+            Self {
+                message,
+                line: get_defined_property(ctx, object, "line")
+                    .map(|line| JSValueToNumber(ctx, line, null_mut()) as u32),
+                source_url: get_defined_property(ctx, object, "sourceURL")
+                    .map(|url| value_to_string(ctx, url)),
+                stack: get_defined_property(ctx, object, "stack")
+                    .map(|stack| value_to_string(ctx, stack)),
+            }
+        }
+    }
+}
+
+impl fmt::Display for JSException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.source_url, self.line) {
+            (Some(source_url), Some(line)) => write!(f, "{source_url}:{line}: ")?,
+            (None, Some(line)) => write!(f, "line {line}: ")?,
+            _ => {}
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for JSException {}
+
+/// Exception thrown by `ViewRef::evaluate_script`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    /// Description of the exception, as Ultralight reports it.
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Create a `JSStringRef`, release it with `JSStringRelease`.
+fn create_js_string(string: &str) -> JSStringRef {
+    let string = CString::new(string).unwrap();
+    unsafe { JSStringCreateWithUTF8CString(string.as_ptr()) }
+}
+
+/// Copy a `JSStringRef` into a `String`.
+unsafe fn read_js_string(string: JSStringRef) -> String {
+    let size = JSStringGetMaximumUTF8CStringSize(string);
+    let mut buffer = vec![0u8; size];
+    let written = JSStringGetUTF8CString(string, buffer.as_mut_ptr() as _, size);
+    // Without the null terminator.
+    buffer.truncate(written.saturating_sub(1));
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// Convert any value to a string like `String(value)` does, empty when that throws.
+unsafe fn value_to_string(ctx: JSContextRef, value: JSValueRef) -> String {
+    let string = JSValueToStringCopy(ctx, value, null_mut());
+    if string.is_null() {
+        return String::new();
+    }
+    let result = read_js_string(string);
+    JSStringRelease(string);
+    result
+}
+
+unsafe fn get_defined_property(
+    ctx: JSContextRef,
+    object: JSObjectRef,
+    name: &str,
+) -> Option<JSValueRef> {
+    let name = create_js_string(name);
+    let value = JSObjectGetProperty(ctx, object, name, null_mut());
+    JSStringRelease(name);
+    (!value.is_null() && !JSValueIsUndefined(ctx, value)).then_some(value)
+}
+
 /* ========================= */
 /*         JSObject          */
 /* ========================= */
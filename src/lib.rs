@@ -1,12 +1,21 @@
+pub mod bitmap;
 pub mod gpu_driver;
 pub mod javascript;
 pub mod platform;
+pub mod rect;
 pub mod renderer;
+pub mod shaders;
+pub mod surface;
 pub mod sys;
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
 
+pub use bitmap::*;
 pub use javascript::*;
 pub use platform::*;
+pub use rect::*;
 pub use renderer::*;
+pub use surface::*;
 use sys::{ulConfigSetAnimationTimerDelay, ulConfigSetCachePath, ulViewConfigSetIsTransparent};
 
 use crate::sys::{
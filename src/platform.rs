@@ -97,7 +97,7 @@ pub fn assets_modified() -> bool {
     false
 }
 
-fn read_ulstring(input: ULString) -> String {
+pub(crate) fn read_ulstring(input: ULString) -> String {
     let msg_length = unsafe { ulStringGetLength(input) };
     let msg_data = unsafe { ulStringGetData(input) };
     let msg_slice = unsafe { std::slice::from_raw_parts(msg_data as *const u8, msg_length) };
@@ -0,0 +1,93 @@
+use crate::sys::{ULIntRect, ULRect};
+
+/// Integer rectangle in pixels, edges are stored the same way Ultralight does.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IntRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl IntRect {
+    pub fn width(&self) -> u32 {
+        (self.right - self.left).max(0) as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.bottom - self.top).max(0) as u32
+    }
+
+    /// Returns whether the rectangle covers no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Returns whether the pixel at `x`, `y` lies inside the rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.right && y >= self.top && y < self.bottom
+    }
+}
+
+impl From<ULIntRect> for IntRect {
+    fn from(value: ULIntRect) -> Self {
+        Self {
+            left: value.left,
+            top: value.top,
+            right: value.right,
+            bottom: value.bottom,
+        }
+    }
+}
+
+impl From<IntRect> for ULIntRect {
+    fn from(value: IntRect) -> Self {
+        Self {
+            left: value.left,
+            top: value.top,
+            right: value.right,
+            bottom: value.bottom,
+        }
+    }
+}
+
+/// Floating point rectangle, used for texture coordinates.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Rect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Rect {
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+}
+
+impl From<ULRect> for Rect {
+    fn from(value: ULRect) -> Self {
+        Self {
+            left: value.left,
+            top: value.top,
+            right: value.right,
+            bottom: value.bottom,
+        }
+    }
+}
+
+impl From<Rect> for ULRect {
+    fn from(value: Rect) -> Self {
+        Self {
+            left: value.left,
+            top: value.top,
+            right: value.right,
+            bottom: value.bottom,
+        }
+    }
+}
@@ -1,165 +1,286 @@
 use super::{Config, ViewConfig};
 use crate::{
+    gpu_driver::RenderTarget,
+    platform::read_ulstring,
     sys::{
-        ulBitmapGetBpp, ulBitmapGetHeight, ulBitmapGetWidth, ulBitmapRawPixels,
         ulBitmapSurfaceGetBitmap, ulCreateKeyEvent, ulCreateMouseEvent, ulCreateRenderer,
-        ulCreateScrollEvent, ulCreateSession, ulCreateString, ulCreateView, ulDestroyKeyEvent,
-        ulDestroyMouseEvent, ulDestroyRenderer, ulDestroyScrollEvent, ulDestroyString,
-        ulDestroyView, ulRefreshDisplay, ulRender, ulStringGetData, ulStringGetLength,
-        ulSurfaceGetDirtyBounds, ulUpdate, ulViewFireKeyEvent, ulViewFireMouseEvent,
+        ulCreateScrollEvent, ulCreateString, ulCreateView, ulDestroyKeyEvent, ulDestroyMouseEvent,
+        ulDestroyRenderer, ulDestroyScrollEvent, ulDestroyString, ulDestroyView, ulRefreshDisplay,
+        ulRender, ulSurfaceClearDirtyBounds, ulSurfaceGetDirtyBounds, ulUpdate, ulViewCanGoBack,
+        ulViewCanGoForward, ulViewEvaluateScript, ulViewFireKeyEvent, ulViewFireMouseEvent,
         ulViewFireScrollEvent, ulViewFocus, ulViewGetNeedsPaint, ulViewGetRenderTarget,
-        ulViewGetSurface, ulViewIsLoading, ulViewLoadURL, ulViewReload, ulViewResize,
-        ulViewSetAddConsoleMessageCallback, ulViewSetDOMReadyCallback,
-        ulViewSetFinishLoadingCallback, ulViewSetNeedsPaint, ulViewUnfocus,
-        ULFinishLoadingCallback, ULKeyEventType_kKeyEventType_Char,
-        ULKeyEventType_kKeyEventType_KeyDown, ULKeyEventType_kKeyEventType_KeyUp, ULMessageLevel,
-        ULMessageSource, ULMouseButton_kMouseButton_Left, ULMouseButton_kMouseButton_None,
-        ULMouseEventType_kMouseEventType_MouseDown, ULMouseEventType_kMouseEventType_MouseMoved,
-        ULMouseEventType_kMouseEventType_MouseUp, ULRenderTarget, ULRenderer,
-        ULScrollEventType_kScrollEventType_ScrollByPage,
-        ULScrollEventType_kScrollEventType_ScrollByPixel, ULSession, ULString, ULView,
+        ulViewGetSurface, ulViewGetTitle, ulViewGetURL, ulViewGoBack, ulViewGoForward,
+        ulViewGoToHistoryOffset, ulViewIsLoading, ulViewLoadHTML, ulViewLoadURL, ulViewReload,
+        ulViewResize, ulViewSetNeedsPaint, ulViewStop, ulViewUnfocus,
+        ULKeyEventType_kKeyEventType_Char, ULKeyEventType_kKeyEventType_KeyDown,
+        ULKeyEventType_kKeyEventType_KeyUp, ULMouseButton_kMouseButton_Left,
+        ULMouseButton_kMouseButton_None, ULMouseEventType_kMouseEventType_MouseDown,
+        ULMouseEventType_kMouseEventType_MouseMoved, ULMouseEventType_kMouseEventType_MouseUp,
+        ULRenderTarget, ULRenderer, ULScrollEventType_kScrollEventType_ScrollByPage,
+        ULScrollEventType_kScrollEventType_ScrollByPixel, ULView,
     },
-    JSContext,
+    AlphaMode, Bitmap, BitmapRef, DirtyRegion, IntRect, JSContext, PixelFormat, ScriptError,
 };
 
-#[cfg(feature = "filewatching")]
-use crate::ASSETS_MODIFIED;
+mod callbacks;
+mod console;
+mod futures;
+mod load;
+mod session;
+
+pub use callbacks::*;
+pub use console::*;
+pub use futures::*;
+pub use load::*;
+pub use session::*;
+
+use callbacks::{register_callbacks, unregister_callbacks, ViewCallbacks};
+use futures::ViewWakers;
 #[cfg(feature = "image")]
 use image::RgbaImage;
-use std::{ffi::CString, os::raw::c_void, ptr::null_mut};
+use load::LoadTracker;
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    ffi::CString,
+    marker::PhantomData,
+    ops::Deref,
+    os::raw::c_void,
+    ptr::null_mut,
+    rc::{Rc, Weak},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-pub struct Renderer {
+/// Set while a renderer exists, Ultralight only supports one per process.
+static RENDERER_ALIVE: AtomicBool = AtomicBool::new(false);
+
+/// Shared by a `Renderer` and its views, so it is destroyed after the last of them.
+struct RendererHandle {
     inner: ULRenderer,
-    session: ULSession,
+    /// Views that are alive, in creation order.
+    views: RefCell<Vec<Weak<ViewState>>>,
+}
+
+impl Drop for RendererHandle {
+    fn drop(&mut self) {
+        // Every view holds a reference to us, so they have all been destroyed by now.
+        debug_assert!(self.views.borrow().is_empty());
+        unsafe {
+            ulDestroyRenderer(self.inner);
+        }
+        RENDERER_ALIVE.store(false, Ordering::Release);
+    }
+}
+
+/// The Ultralight renderer, at most one can exist at a time.
+///
+/// Views keep the underlying renderer alive, dropping the `Renderer` before its views is fine.
+pub struct Renderer {
+    handle: Rc<RendererHandle>,
+    /// Session `create_view` uses.
+    session: Session,
 }
 
 impl Renderer {
     /// Create a new renderer.
+    ///
+    /// # Panics
+    /// When another renderer, or a view of one, is still alive.
     pub fn new(config: &Config) -> Self {
-        let inner = unsafe { ulCreateRenderer(config.into()) };
+        if RENDERER_ALIVE.swap(true, Ordering::AcqRel) {
+            panic!("Only one Renderer can exist at a time");
+        }
 
-        let text = CString::new("ulsession").unwrap();
-        let text = unsafe { ulCreateString(text.as_ptr()) };
-        let session = unsafe { ulCreateSession(inner, true, text) };
-        unsafe { ulDestroyString(text) };
+        let inner = unsafe { ulCreateRenderer(config.into()) };
+        let handle = Rc::new(RendererHandle {
+            inner,
+            views: RefCell::new(Vec::new()),
+        });
+        let session = Session::new(&handle, true, "ulsession");
 
-        Self { inner, session }
+        Self { handle, session }
     }
 
-    /// Create a View with certain size (in pixels).
+    /// Create a View with certain size (in pixels), in the persistent session named `"ulsession"`.
     pub fn create_view(&mut self, width: u32, height: u32, config: &ViewConfig) -> View {
-        let view = unsafe { ulCreateView(self.inner, width, height, config.into(), self.session) };
-        let mut view = View::from(view);
-        view.set_finish_loading_callback(Some(on_finish_loading));
+        let session = self.session.clone();
+        self.create_view_in(&session, width, height, config)
+    }
+
+    /// Create a View with certain size (in pixels) that stores its data in `session`.
+    pub fn create_view_in(
+        &mut self,
+        session: &Session,
+        width: u32,
+        height: u32,
+        config: &ViewConfig,
+    ) -> View {
+        let handle = &self.handle;
+        let view =
+            unsafe { ulCreateView(handle.inner, width, height, config.into(), session.into()) };
+        let state = Rc::new(ViewState {
+            inner: view,
+            renderer: handle.clone(),
+            session: session.clone(),
+            load: RefCell::new(LoadTracker::default()),
+            wakers: ViewWakers::default(),
+            callbacks: ViewCallbacks::default(),
+        });
+        handle.views.borrow_mut().push(Rc::downgrade(&state));
+
+        let view = View {
+            view: ViewRef::new(state),
+        };
+        unsafe { register_callbacks(view.inner(), view.user_data()) };
 
         view
     }
 
+    /// Create a session named `name`, keeping its data on disk when `persistent`.
+    ///
+    /// Persistent sessions with the same name share their data, also across runs.
+    pub fn create_session(&self, persistent: bool, name: &str) -> Session {
+        Session::new(&self.handle, persistent, name)
+    }
+
+    /// Ultralight's own persistent session, named `"default"`.
+    pub fn default_session(&self) -> Session {
+        Session::renderer_default(&self.handle)
+    }
+
+    /// Session `create_view` creates views in.
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Views of this renderer that are still alive, in creation order.
+    pub fn views(&self) -> Vec<ViewRef<'_>> {
+        self.handle
+            .views
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(ViewRef::new)
+            .collect()
+    }
+
     /// Render all active `Views`.
     pub fn render(&mut self) {
         unsafe {
-            ulRender(self.inner);
+            ulRender(self.handle.inner);
         }
     }
 
     /// Update timers and dispatch internal callbacks (JavaScript and network).
     pub fn update(&mut self) {
         unsafe {
-            ulRefreshDisplay(self.inner, 0); // TODO: Move to after vsync
-            ulUpdate(self.inner);
+            ulRefreshDisplay(self.handle.inner, 0); // TODO: Move to after vsync
+            ulUpdate(self.handle.inner);
         }
+        self.handle.wake_load_futures();
+    }
+}
+
+impl From<&Renderer> for ULRenderer {
+    fn from(value: &Renderer) -> Self {
+        value.handle.inner
     }
 }
 
-impl Drop for Renderer {
+/// State shared by a `View` and the `ViewRef`s to it, destroys the view once the last is dropped.
+///
+/// Its address is passed to Ultralight as `user_data` of the view's callbacks.
+struct ViewState {
+    inner: ULView,
+    /// Keeps the renderer alive while the view exists.
+    renderer: Rc<RendererHandle>,
+    session: Session,
+    load: RefCell<LoadTracker>,
+    wakers: ViewWakers,
+    callbacks: ViewCallbacks,
+}
+
+impl Drop for ViewState {
     fn drop(&mut self) {
         unsafe {
-            ulDestroyRenderer(self.inner);
+            unregister_callbacks(self.inner);
+            ulDestroyView(self.inner);
         }
-    }
-}
+        self.wakers.close();
 
-impl From<Renderer> for ULRenderer {
-    fn from(value: Renderer) -> Self {
-        value.inner
+        // Our own entry can't be upgraded anymore. The renderer is destroyed after this when we
+        // held the last reference to it.
+        self.renderer
+            .views
+            .borrow_mut()
+            .retain(|view| view.strong_count() > 0);
     }
 }
 
+/// A view created by `Renderer::create_view`, destroyed when dropped.
+///
+/// Everything you can do with a view is available through `ViewRef`, which `View` derefs to.
 pub struct View {
-    owned: bool,
-    inner: ULView,
-    is_ready: Box<bool>,
+    view: ViewRef<'static>,
+}
 
-    dom_ready_callback: Option<*mut c_void>, // Raw pointer passed to ultralight.
+impl Deref for View {
+    type Target = ViewRef<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.view
+    }
 }
 
-pub extern "C" fn console_callback_wrapper(
-    user_data: *mut c_void,
-    _caller: ULView,
-    _source: ULMessageSource,
-    level: ULMessageLevel,
-    message: ULString,
-    _line_number: ::std::os::raw::c_uint,
-    _column_number: ::std::os::raw::c_uint,
-    _source_id: ULString,
-) {
-    let msg_length = unsafe { ulStringGetLength(message) };
-    let msg_data = unsafe { ulStringGetData(message) };
-    let msg_slice = unsafe { std::slice::from_raw_parts(msg_data as *const u8, msg_length) };
-    let msg = String::from_utf8(msg_slice.to_vec()).unwrap();
-
-    let safe_callback_ptr: fn(ULMessageLevel, String) = unsafe { std::mem::transmute(user_data) };
-    safe_callback_ptr(level, msg);
+/// Borrowed view, handed to callbacks and returned by `Renderer::views`.
+///
+/// Shares its state with the owning `View`, so the load state and callbacks behave the same.
+pub struct ViewRef<'a> {
+    state: Rc<ViewState>,
+    _lifetime: PhantomData<&'a ViewState>,
 }
 
-impl View {
-    /// Set callback for when the page finishes loading a URL into a frame.
-    pub fn set_finish_loading_callback(&mut self, callback: ULFinishLoadingCallback) {
-        unsafe {
-            ulViewSetFinishLoadingCallback(
-                self.inner,
-                callback,
-                self.is_ready.as_mut() as *mut _ as _,
-            );
+impl ViewRef<'_> {
+    fn new(state: Rc<ViewState>) -> Self {
+        Self {
+            state,
+            _lifetime: PhantomData,
         }
     }
 
-    /// Set callback for the javascript console.
-    /// This gets called when javascript calls `console.log` for example.
-    /// But also shows javascript warnings and errors.
-    pub fn set_console_callback(&mut self, callback: fn(ULMessageLevel, String)) {
-        unsafe {
-            let callback_ptr = callback as *mut c_void;
-            ulViewSetAddConsoleMessageCallback(
-                self.inner,
-                Some(console_callback_wrapper),
-                callback_ptr,
-            );
-        }
+    /// Borrow the view whose `ViewState` was passed to Ultralight as `user_data`.
+    ///
+    /// # Safety
+    /// `user_data` has to point to a `ViewState` that is alive, which it is while Ultralight can
+    /// still call the view's callbacks.
+    unsafe fn from_user_data(user_data: *mut c_void) -> Self {
+        let state = user_data as *const ViewState;
+        Rc::increment_strong_count(state);
+        Self::new(Rc::from_raw(state))
     }
 
-    pub fn get_render_target(&self) -> ULRenderTarget {
-        unsafe { ulViewGetRenderTarget(self.inner) }
+    /// Session the view stores its data in.
+    pub fn session(&self) -> &Session {
+        &self.state.session
     }
 
-    /// Set callback for when the page finishes loading a URL into a frame.
-    pub fn set_dom_ready_callback<F>(&mut self, callback: F)
-    where
-        F: FnMut(View),
-        F: 'static,
-    {
-        let dom_ready_callback: Box<Box<dyn FnMut(View)>> = Box::new(Box::new(callback));
+    fn user_data(&self) -> *mut c_void {
+        Rc::as_ptr(&self.state) as *mut c_void
+    }
+}
 
-        let func_pointer = Box::into_raw(dom_ready_callback) as *mut c_void;
-        unsafe {
-            ulViewSetDOMReadyCallback(self.inner, Some(dom_ready_wrapper), func_pointer);
-        }
+impl ViewRef<'_> {
+    pub fn get_render_target(&self) -> ULRenderTarget {
+        unsafe { ulViewGetRenderTarget(self.inner()) }
+    }
 
-        self.dom_ready_callback = Some(func_pointer);
+    /// Texture a GPU accelerated view was rendered into, `None` for CPU views.
+    pub fn render_target(&self) -> Option<RenderTarget> {
+        RenderTarget::from_raw(self.get_render_target())
     }
 
     pub fn key_event(
-        &mut self,
+        &self,
         virtual_key_code: i32,
         native_key_code: i32,
         modifiers: u32,
@@ -182,7 +303,7 @@ impl View {
                 false,
             );
 
-            ulViewFireKeyEvent(self.inner, event);
+            ulViewFireKeyEvent(self.inner(), event);
             ulDestroyKeyEvent(event);
         }
     }
@@ -204,7 +325,7 @@ impl View {
                 false,
             );
 
-            ulViewFireKeyEvent(self.inner, event);
+            ulViewFireKeyEvent(self.inner(), event);
             ulDestroyKeyEvent(event);
             ulDestroyString(text);
         }
@@ -221,7 +342,7 @@ impl View {
                 x,
                 y,
             );
-            ulViewFireScrollEvent(self.inner, event);
+            ulViewFireScrollEvent(self.inner(), event);
             ulDestroyScrollEvent(event);
         }
     }
@@ -238,7 +359,7 @@ impl View {
                 y,
                 ULMouseButton_kMouseButton_Left,
             );
-            ulViewFireMouseEvent(self.inner, event);
+            ulViewFireMouseEvent(self.inner(), event);
             ulDestroyMouseEvent(event);
         }
     }
@@ -251,190 +372,400 @@ impl View {
                 y,
                 ULMouseButton_kMouseButton_None,
             );
-            ulViewFireMouseEvent(self.inner, event);
+            ulViewFireMouseEvent(self.inner(), event);
             ulDestroyMouseEvent(event);
         }
     }
 
     pub fn resize(&self, width: u32, height: u32) {
-        unsafe { ulViewResize(self.inner, width, height) };
+        unsafe { ulViewResize(self.inner(), width, height) };
     }
 
     pub fn set_focus(&self, bool: bool) {
         if bool {
-            unsafe { ulViewFocus(self.inner) };
+            unsafe { ulViewFocus(self.inner()) };
         } else {
-            unsafe { ulViewUnfocus(self.inner) };
+            unsafe { ulViewUnfocus(self.inner()) };
         }
     }
 
     pub fn set_needs_repaint(&self, val: bool) {
-        unsafe { ulViewSetNeedsPaint(self.inner, val) };
+        unsafe { ulViewSetNeedsPaint(self.inner(), val) };
     }
 
     pub fn reload(&self) {
+        self.state.load.borrow_mut().start();
         unsafe {
-            ulViewReload(self.inner);
+            ulViewReload(self.inner());
         }
     }
 
     /// Load a URL into main frame.
     pub fn load_url(&self, string: String) {
+        self.state.load.borrow_mut().start();
         unsafe {
             let url_string = CString::new(string).unwrap();
             let url_string = ulCreateString(url_string.as_ptr());
-            ulViewLoadURL(self.inner, url_string);
+            ulViewLoadURL(self.inner(), url_string);
             ulDestroyString(url_string);
         }
     }
 
-    /// Returns whether the main frame is loaded.
+    /// Load a string of HTML into main frame.
+    ///
+    /// Relative URLs in the document resolve against `base_url`, for example `file:///` to load
+    /// assets through the platform file system. Without one they resolve against `about:blank`.
+    ///
+    /// Ultralight has no base URL parameter, `base_url` is added to the document as a `<base>`
+    /// tag. A `<base>` the document already has takes precedence.
+    pub fn load_html(&self, html: &str, base_url: Option<&str>) {
+        let html = match base_url {
+            Some(base_url) => Cow::Owned(insert_base_url(html, base_url)),
+            None => Cow::Borrowed(html),
+        };
+
+        self.state.load.borrow_mut().start();
+        unsafe {
+            let html = CString::new(html.as_ref()).unwrap();
+            let html = ulCreateString(html.as_ptr());
+            ulViewLoadHTML(self.inner(), html);
+            ulDestroyString(html);
+        }
+    }
+
+    /// Evaluate `script` in the main frame, returning its result converted to a string.
+    ///
+    /// Use `JSContext::evaluate` for typed results and the location of exceptions.
+    pub fn evaluate_script(&self, script: &str) -> Result<String, ScriptError> {
+        unsafe {
+            let script = CString::new(script).unwrap();
+            let script = ulCreateString(script.as_ptr());
+            let mut exception = null_mut();
+            // Both strings are owned by the view.
+            let result = ulViewEvaluateScript(self.inner(), script, &mut exception);
+            ulDestroyString(script);
+
+            if !exception.is_null() {
+                let message = read_ulstring(exception);
+                if !message.is_empty() {
+                    return Err(ScriptError { message });
+                }
+            }
+            Ok(read_ulstring(result))
+        }
+    }
+
+    /// URL of the main frame, empty before anything was loaded.
+    pub fn url(&self) -> String {
+        read_ulstring(unsafe { ulViewGetURL(self.inner()) })
+    }
+
+    /// Title of the page in the main frame.
+    pub fn title(&self) -> String {
+        read_ulstring(unsafe { ulViewGetTitle(self.inner()) })
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        unsafe { ulViewCanGoBack(self.inner()) }
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        unsafe { ulViewCanGoForward(self.inner()) }
+    }
+
+    pub fn go_back(&self) {
+        unsafe { ulViewGoBack(self.inner()) };
+    }
+
+    pub fn go_forward(&self) {
+        unsafe { ulViewGoForward(self.inner()) };
+    }
+
+    /// Navigate `offset` entries through the history, negative offsets go back.
+    pub fn go_to_history_offset(&self, offset: i32) {
+        unsafe { ulViewGoToHistoryOffset(self.inner(), offset) };
+    }
+
+    /// Stop all page loads.
+    pub fn stop(&self) {
+        unsafe { ulViewStop(self.inner()) };
+    }
+
+    /// Returns whether the main frame is loaded and no frame is loading anymore.
     pub fn is_ready(&self) -> bool {
-        let loading = unsafe { ulViewIsLoading(self.inner) };
-        *self.is_ready && !loading
+        let loading = unsafe { ulViewIsLoading(self.inner()) };
+        self.load_state() == LoadState::Loaded && !loading
+    }
+
+    /// Load state of the main frame.
+    ///
+    /// Becomes `Loading` as soon as `load_url`, `load_html` or `reload` is called, the other
+    /// states are reached while `Renderer::update` runs.
+    pub fn load_state(&self) -> LoadState {
+        self.state.load.borrow().main_frame().clone()
+    }
+
+    /// Load state of the frame with `frame_id`, `None` for frames not seen since the main frame
+    /// began loading.
+    pub fn frame_load_state(&self, frame_id: u64) -> Option<LoadState> {
+        self.state.load.borrow().frame(frame_id).cloned()
+    }
+
+    /// Take the load events that happened since the last call, oldest first.
+    ///
+    /// Events are queued while `Renderer::update` runs, the oldest are dropped when many pile up.
+    pub fn take_load_events(&self) -> Vec<LoadEvent> {
+        self.state.load.borrow_mut().take_events()
     }
 
-    /// Get the surface of the `View` as a `RgbaImage`.
+    /// Get the surface of the `View` as a `RgbaImage`, `None` when the view is GPU accelerated.
     #[cfg(feature = "image")]
-    pub fn get_image(&self) -> RgbaImage {
-        unsafe {
-            let surface = ulViewGetSurface(self.inner);
-            let bitmap: *mut crate::sys::C_Bitmap = ulBitmapSurfaceGetBitmap(surface);
-
-            let width = ulBitmapGetWidth(bitmap);
-            let height = ulBitmapGetHeight(bitmap);
-            crate::sys::ulBitmapSwapRedBlueChannels(bitmap);
-            let pixels_ptr = ulBitmapRawPixels(bitmap);
-            let bytes_per_pixel = ulBitmapGetBpp(bitmap);
-            let pixels: &[u8] = std::slice::from_raw_parts(
-                pixels_ptr as _,
-                (width * height * bytes_per_pixel) as usize,
-            );
+    pub fn get_image(&self) -> Option<RgbaImage> {
+        self.with_bitmap(|bitmap| {
+            let pixels = bitmap.read_pixels(PixelFormat::Rgba8, AlphaMode::Straight)?;
+            RgbaImage::from_vec(bitmap.width(), bitmap.height(), pixels)
+        })
+        .flatten()
+    }
 
-            RgbaImage::from_vec(width, height, pixels.to_vec()).unwrap()
-        }
+    /// Copy the surface into a tightly packed buffer of `format`.
+    ///
+    /// Unlike rendering into the surface this never modifies the view's pixels. `None` when the
+    /// view is GPU accelerated.
+    pub fn read_pixels(&self, format: PixelFormat, alpha: AlphaMode) -> Option<Vec<u8>> {
+        self.with_bitmap(|bitmap| bitmap.read_pixels(format, alpha))
+            .flatten()
     }
 
     pub fn needs_repaint(&self) -> bool {
-        unsafe { ulViewGetNeedsPaint(self.inner) }
+        unsafe { ulViewGetNeedsPaint(self.inner()) }
     }
 
-    /// Returns what area of the surface was written to
-    /// (left, right, top, bottom)
-    pub fn dirty_bounds(&self) -> [u32; 4] {
+    /// Returns what area of the surface was written to since the bounds were last cleared.
+    pub fn dirty_bounds(&self) -> IntRect {
         unsafe {
-            let surface = ulViewGetSurface(self.inner);
-            let rect = ulSurfaceGetDirtyBounds(surface);
-            [
-                rect.left as u32,
-                rect.right as u32,
-                rect.top as u32,
-                rect.bottom as u32,
-            ]
+            let surface = ulViewGetSurface(self.inner());
+            if surface.is_null() {
+                return IntRect::default();
+            }
+
+            IntRect::from(ulSurfaceGetDirtyBounds(surface))
         }
     }
 
-    pub fn bitmap_size(&self) -> (u32, u32) {
+    /// Copy the pixels that changed since the last call, `None` when nothing changed.
+    ///
+    /// Clears the dirty bounds.
+    pub fn dirty_region(&self) -> Option<DirtyRegion> {
         unsafe {
-            let surface = ulViewGetSurface(self.inner);
-            let bitmap: *mut crate::sys::C_Bitmap = ulBitmapSurfaceGetBitmap(surface);
-            (ulBitmapGetWidth(bitmap), ulBitmapGetHeight(bitmap))
+            let surface = ulViewGetSurface(self.inner());
+            if surface.is_null() {
+                return None;
+            }
+
+            DirtyRegion::take(surface)
         }
     }
 
-    pub fn get_image_raw(&self) -> &[u8] {
+    /// Mark the whole surface as clean without reading it.
+    pub fn clear_dirty_bounds(&self) {
         unsafe {
-            let surface = ulViewGetSurface(self.inner);
-            let bitmap: *mut crate::sys::C_Bitmap = ulBitmapSurfaceGetBitmap(surface);
-
-            let width = ulBitmapGetWidth(bitmap);
-            let height = ulBitmapGetHeight(bitmap);
-            let pixels_ptr = ulBitmapRawPixels(bitmap);
-            let bytes_per_pixel = ulBitmapGetBpp(bitmap);
-            std::slice::from_raw_parts(pixels_ptr as _, (width * height * bytes_per_pixel) as usize)
+            let surface = ulViewGetSurface(self.inner());
+            if !surface.is_null() {
+                ulSurfaceClearDirtyBounds(surface);
+            }
         }
     }
 
-    pub fn lock_jscontext(&self) -> JSContext<'_> {
-        JSContext::new(&self)
+    /// Size of the surface bitmap, `None` when the view is GPU accelerated.
+    pub fn bitmap_size(&self) -> Option<(u32, u32)> {
+        self.with_bitmap(|bitmap| (bitmap.width(), bitmap.height()))
     }
-}
 
-impl Drop for View {
-    fn drop(&mut self) {
-        if self.owned {
-            if let Some(ptr) = self.dom_ready_callback.take() {
-                let _: Box<Box<dyn FnMut(View)>> = unsafe { Box::from_raw(ptr as *mut _) };
-            }
+    /// Copy of the surface bitmap, `None` when the view is GPU accelerated.
+    ///
+    /// A copy, because rendering or resizing the view can rewrite or replace the surface.
+    pub fn bitmap(&self) -> Option<Bitmap> {
+        self.with_bitmap(Bitmap::clone)
+    }
 
-            unsafe {
-                ulViewSetFinishLoadingCallback(self.inner, None, null_mut());
-                ulDestroyView(self.inner);
+    /// Run `f` with the surface bitmap, which nothing can touch while `f` runs.
+    fn with_bitmap<R>(&self, f: impl FnOnce(&Bitmap) -> R) -> Option<R> {
+        unsafe {
+            let surface = ulViewGetSurface(self.inner());
+            if surface.is_null() {
+                return None;
             }
+
+            Some(f(&BitmapRef::from_raw(ulBitmapSurfaceGetBitmap(surface))))
         }
     }
+
+    /// Copy of the surface as tightly packed, premultiplied BGRA pixels, `None` when the view is
+    /// GPU accelerated.
+    pub fn get_image_raw(&self) -> Option<Vec<u8>> {
+        self.read_pixels(PixelFormat::Bgra8, AlphaMode::Premultiplied)
+    }
+
+    pub fn lock_jscontext(&self) -> JSContext<'_> {
+        JSContext::new(self)
+    }
+
+    fn inner(&self) -> ULView {
+        self.state.inner
+    }
 }
 
 impl From<&View> for ULView {
     fn from(value: &View) -> Self {
-        value.inner
+        value.inner()
     }
 }
 
-// TODO: this is akward with the owned value
-impl From<ULView> for View {
-    fn from(value: ULView) -> Self {
-        Self {
-            inner: value,
-            is_ready: Box::new(false),
-            owned: true,
-            dom_ready_callback: None,
-        }
+impl From<&ViewRef<'_>> for ULView {
+    fn from(value: &ViewRef<'_>) -> Self {
+        value.inner()
     }
 }
 
-// TODO: this is akward with the owned value
-impl From<&ULView> for View {
-    fn from(value: &ULView) -> Self {
-        Self {
-            inner: value.clone(),
-            is_ready: Box::new(false),
-            owned: false,
-            dom_ready_callback: None,
-        }
-    }
+/// Insert a `<base>` tag with `base_url` into `html`.
+///
+/// It goes at the start of `<head>` so it applies to every URL in the document. Documents
+/// without a `<head>` get it after the doctype, which keeps them out of quirks mode. Documents
+/// with a `<base>` of their own are left alone.
+fn insert_base_url(html: &str, base_url: &str) -> String {
+    let mut html = html.to_owned();
+    let Some(position) = base_url_position(&html) else {
+        return html;
+    };
+
+    let base = format!(
+        "<base href=\"{}\">",
+        base_url.replace('&', "&amp;").replace('"', "&quot;")
+    );
+    html.insert_str(position, &base);
+    html
 }
 
-pub extern "C" fn on_finish_loading(
-    user_data: *mut c_void,
-    _caller: ULView,
-    _frame_id: u64,
-    is_main_frame: bool,
-    _url: ULString,
-) {
-    #[cfg(feature = "filewatching")]
-    unsafe {
-        *ASSETS_MODIFIED.write().unwrap() = false;
+/// Where `insert_base_url` puts the `<base>` tag, `None` when the document has one.
+///
+/// Walks the tags in front of the body, skipping comments, attribute values and the contents of
+/// elements like `<script>` that can contain anything looking like a tag.
+fn base_url_position(html: &str) -> Option<usize> {
+    // Lowercasing ASCII keeps every byte where it is.
+    let html = html.to_ascii_lowercase();
+    let mut doctype_end = None;
+    let mut head_end = None;
+
+    let mut i = 0;
+    while let Some(offset) = html[i..].find('<') {
+        let start = i + offset;
+        if html[start..].starts_with("<!--") {
+            i = html[start..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+
+        let end = tag_end(&html, start);
+        let name = html[start + 1..end]
+            .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            .find(|name| !name.is_empty())
+            .unwrap_or_default();
+        if html[start + 1..].starts_with('/') {
+            if name == "head" {
+                break;
+            }
+            i = end;
+            continue;
+        }
+        match name {
+            "!doctype" => doctype_end = Some(end),
+            "head" if head_end.is_none() => head_end = Some(end),
+            "base" => return None,
+            "body" => break,
+            "script" | "style" | "textarea" | "title" => {
+                let close = format!("</{}", name);
+                i = html[end..]
+                    .find(&close)
+                    .map_or(html.len(), |close| end + close);
+                continue;
+            }
+            _ => {}
+        }
+        i = end;
     }
 
-    if is_main_frame {
-        let is_ready: *mut bool = user_data as _;
-        unsafe { *is_ready = true };
+    Some(head_end.or(doctype_end).unwrap_or(0))
+}
+
+/// Index right after the `>` closing the tag at `start`, ignoring `>` in quoted attribute values.
+fn tag_end(html: &str, start: usize) -> usize {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (offset, c) in html[start..].char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '>' => return start + offset + 1,
+            None if (c == '"' || c == '\'') && previous == '=' => quote = Some(c),
+            None => {}
+        }
+        if !c.is_ascii_whitespace() {
+            previous = c;
+        }
     }
+    html.len()
 }
 
-/// Wraps rust callbacks for `JSObject`
-unsafe extern "C" fn dom_ready_wrapper(
-    user_data: *mut std::os::raw::c_void,
-    caller: ULView,
-    _frame_id: u64,
-    _is_main_frame: bool,
-    _url: ULString,
-) {
-    let view = View::from(&caller);
-
-    unsafe {
-        let closure: &mut Box<dyn FnMut(View)> = std::mem::transmute(user_data);
-        closure(view);
+#[cfg(test)]
+mod tests {
+    use super::insert_base_url;
+
+    const BASE: &str = "<base href=\"file:///\">";
+
+    #[test]
+    fn base_url_goes_into_head() {
+        assert_eq!(
+            insert_base_url(
+                "<!DOCTYPE html><html><HEAD lang=\"en\"></head></html>",
+                "file:///"
+            ),
+            format!("<!DOCTYPE html><html><HEAD lang=\"en\">{BASE}</head></html>")
+        );
+    }
+
+    #[test]
+    fn base_url_without_head() {
+        assert_eq!(
+            insert_base_url("<!doctype html><p>hi</p>", "file:///"),
+            format!("<!doctype html>{BASE}<p>hi</p>")
+        );
+        assert_eq!(
+            insert_base_url("<p>hi</p>", "file:///"),
+            format!("{BASE}<p>hi</p>")
+        );
+    }
+
+    #[test]
+    fn base_url_skips_lookalikes() {
+        let html = "<!-- <head> --><html data-x=\"<head>\"><header></header>\
+                    <script>let s = '<head>';</script><head></head>";
+        let expected = html.replacen("<head></head>", &format!("<head>{BASE}</head>"), 1);
+        assert_eq!(insert_base_url(html, "file:///"), expected);
+    }
+
+    #[test]
+    fn base_url_keeps_existing_base() {
+        let html = "<html><head><base href=\"https://example.com/\"></head></html>";
+        assert_eq!(insert_base_url(html, "file:///"), html);
+    }
+
+    #[test]
+    fn base_url_is_escaped() {
+        assert_eq!(
+            insert_base_url("", "a\"b&c"),
+            "<base href=\"a&quot;b&amp;c\">"
+        );
     }
 }
@@ -0,0 +1,583 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    os::raw::{c_int, c_uint, c_ulonglong, c_void},
+};
+
+use super::{ConsoleMessage, MessageLevel, MessageSource, ViewEvent, ViewRef};
+use crate::{
+    platform::read_ulstring,
+    sys::{
+        ulViewSetAddConsoleMessageCallback, ulViewSetBeginLoadingCallback,
+        ulViewSetChangeCursorCallback, ulViewSetChangeTitleCallback,
+        ulViewSetChangeTooltipCallback, ulViewSetChangeURLCallback, ulViewSetDOMReadyCallback,
+        ulViewSetFailLoadingCallback, ulViewSetFinishLoadingCallback,
+        ulViewSetUpdateHistoryCallback, ulViewSetWindowObjectReadyCallback, ULCursor,
+        ULCursor_kCursor_Custom, ULCursor_kCursor_Pointer, ULMessageLevel, ULMessageSource,
+        ULString, ULView,
+    },
+};
+
+#[cfg(feature = "filewatching")]
+use crate::ASSETS_MODIFIED;
+
+/// Mouse cursor a page asks for, see `ViewRef::set_change_cursor_callback`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    Pointer,
+    Cross,
+    Hand,
+    IBeam,
+    Wait,
+    Help,
+    EastResize,
+    NorthResize,
+    NorthEastResize,
+    NorthWestResize,
+    SouthResize,
+    SouthEastResize,
+    SouthWestResize,
+    WestResize,
+    NorthSouthResize,
+    EastWestResize,
+    NorthEastSouthWestResize,
+    NorthWestSouthEastResize,
+    ColumnResize,
+    RowResize,
+    MiddlePanning,
+    EastPanning,
+    NorthPanning,
+    NorthEastPanning,
+    NorthWestPanning,
+    SouthPanning,
+    SouthEastPanning,
+    SouthWestPanning,
+    WestPanning,
+    Move,
+    VerticalText,
+    Cell,
+    ContextMenu,
+    Alias,
+    Progress,
+    NoDrop,
+    Copy,
+    None,
+    NotAllowed,
+    ZoomIn,
+    ZoomOut,
+    Grab,
+    Grabbing,
+    Custom,
+}
+
+/// Indexed by `ULCursor`, which numbers the cursors from 0 in this order.
+const CURSORS: [Cursor; 44] = [
+    Cursor::Pointer,
+    Cursor::Cross,
+    Cursor::Hand,
+    Cursor::IBeam,
+    Cursor::Wait,
+    Cursor::Help,
+    Cursor::EastResize,
+    Cursor::NorthResize,
+    Cursor::NorthEastResize,
+    Cursor::NorthWestResize,
+    Cursor::SouthResize,
+    Cursor::SouthEastResize,
+    Cursor::SouthWestResize,
+    Cursor::WestResize,
+    Cursor::NorthSouthResize,
+    Cursor::EastWestResize,
+    Cursor::NorthEastSouthWestResize,
+    Cursor::NorthWestSouthEastResize,
+    Cursor::ColumnResize,
+    Cursor::RowResize,
+    Cursor::MiddlePanning,
+    Cursor::EastPanning,
+    Cursor::NorthPanning,
+    Cursor::NorthEastPanning,
+    Cursor::NorthWestPanning,
+    Cursor::SouthPanning,
+    Cursor::SouthEastPanning,
+    Cursor::SouthWestPanning,
+    Cursor::WestPanning,
+    Cursor::Move,
+    Cursor::VerticalText,
+    Cursor::Cell,
+    Cursor::ContextMenu,
+    Cursor::Alias,
+    Cursor::Progress,
+    Cursor::NoDrop,
+    Cursor::Copy,
+    Cursor::None,
+    Cursor::NotAllowed,
+    Cursor::ZoomIn,
+    Cursor::ZoomOut,
+    Cursor::Grab,
+    Cursor::Grabbing,
+    Cursor::Custom,
+];
+
+const _: () = assert!(ULCursor_kCursor_Pointer == 0);
+const _: () = assert!(ULCursor_kCursor_Custom as usize == CURSORS.len() - 1);
+
+impl Cursor {
+    pub(crate) fn from_raw(value: ULCursor) -> Option<Self> {
+        CURSORS.get(usize::try_from(value).ok()?).copied()
+    }
+}
+
+/// Frame a loading callback is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameEvent {
+    pub frame_id: u64,
+    pub is_main_frame: bool,
+    pub url: String,
+}
+
+impl FrameEvent {
+    fn new(frame_id: c_ulonglong, is_main_frame: bool, url: ULString) -> Self {
+        Self {
+            frame_id,
+            is_main_frame,
+            url: read_ulstring(url),
+        }
+    }
+}
+
+/// Why loading a URL into a frame failed, see `ViewRef::set_fail_loading_callback`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    pub frame: FrameEvent,
+    pub description: String,
+    pub error_domain: String,
+    pub error_code: i32,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to load {}: {} ({} {})",
+            self.frame.url, self.description, self.error_domain, self.error_code
+        )
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Closure of one callback.
+struct Slot<F: ?Sized>(RefCell<Option<Box<F>>>);
+
+impl<F: ?Sized> Default for Slot<F> {
+    fn default() -> Self {
+        Self(RefCell::new(None))
+    }
+}
+
+impl<F: ?Sized> Slot<F> {
+    fn set(&self, callback: Box<F>) {
+        // Dropped after the borrow ends, in case dropping it touches the view.
+        let _previous = self.0.replace(Some(callback));
+    }
+
+    /// Run the closure, it is taken out meanwhile so it can replace itself.
+    fn call(&self, f: impl FnOnce(&mut F)) {
+        let Some(mut callback) = self.0.take() else {
+            return;
+        };
+        f(&mut callback);
+
+        let mut slot = self.0.borrow_mut();
+        let replaced = match *slot {
+            None => {
+                *slot = Some(callback);
+                None
+            }
+            Some(_) => Some(callback),
+        };
+        drop(slot);
+        drop(replaced);
+    }
+}
+
+type FrameCallback = dyn FnMut(ViewRef<'_>, &FrameEvent);
+type TextCallback = dyn FnMut(ViewRef<'_>, &str);
+type FailLoadingCallback = dyn FnMut(ViewRef<'_>, &LoadError);
+type ConsoleCallback = dyn FnMut(ViewRef<'_>, &ConsoleMessage);
+
+/// Closures of the callbacks of a view.
+#[derive(Default)]
+pub(super) struct ViewCallbacks {
+    change_title: Slot<TextCallback>,
+    change_url: Slot<TextCallback>,
+    change_tooltip: Slot<TextCallback>,
+    change_cursor: Slot<dyn FnMut(ViewRef<'_>, Cursor)>,
+    console: Slot<ConsoleCallback>,
+    begin_loading: Slot<FrameCallback>,
+    finish_loading: Slot<FrameCallback>,
+    fail_loading: Slot<FailLoadingCallback>,
+    window_object_ready: Slot<FrameCallback>,
+    dom_ready: Slot<FrameCallback>,
+    update_history: Slot<dyn FnMut(ViewRef<'_>)>,
+}
+
+impl ViewRef<'_> {
+    /// Set callback for when the page title changes.
+    pub fn set_change_title_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &str),
+        F: 'static,
+    {
+        self.state.callbacks.change_title.set(Box::new(callback));
+        unsafe {
+            ulViewSetChangeTitleCallback(self.inner(), Some(change_title), self.user_data());
+        }
+    }
+
+    /// Set callback for when the page URL changes.
+    pub fn set_change_url_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &str),
+        F: 'static,
+    {
+        self.state.callbacks.change_url.set(Box::new(callback));
+        unsafe {
+            ulViewSetChangeURLCallback(self.inner(), Some(change_url), self.user_data());
+        }
+    }
+
+    /// Set callback for when the tooltip changes, usually because the mouse hovers something.
+    pub fn set_change_tooltip_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &str),
+        F: 'static,
+    {
+        self.state.callbacks.change_tooltip.set(Box::new(callback));
+        unsafe {
+            ulViewSetChangeTooltipCallback(self.inner(), Some(change_tooltip), self.user_data());
+        }
+    }
+
+    /// Set callback for when the page wants another mouse cursor.
+    pub fn set_change_cursor_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, Cursor),
+        F: 'static,
+    {
+        self.state.callbacks.change_cursor.set(Box::new(callback));
+        unsafe {
+            ulViewSetChangeCursorCallback(self.inner(), Some(change_cursor), self.user_data());
+        }
+    }
+
+    /// Set callback for the javascript console.
+    /// This gets called when javascript calls `console.log` for example.
+    /// But also shows javascript warnings and errors.
+    pub fn set_console_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &ConsoleMessage),
+        F: 'static,
+    {
+        self.state.callbacks.console.set(Box::new(callback));
+        unsafe {
+            ulViewSetAddConsoleMessageCallback(self.inner(), Some(console), self.user_data());
+        }
+    }
+
+    /// Set callback for when the page begins loading a URL into a frame.
+    pub fn set_begin_loading_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &FrameEvent),
+        F: 'static,
+    {
+        // Always registered, the load state depends on it.
+        self.state.callbacks.begin_loading.set(Box::new(callback));
+    }
+
+    /// Set callback for when the page finishes loading a URL into a frame.
+    pub fn set_finish_loading_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &FrameEvent),
+        F: 'static,
+    {
+        // Always registered, the load state depends on it.
+        self.state.callbacks.finish_loading.set(Box::new(callback));
+    }
+
+    /// Set callback for when loading a URL into a frame fails.
+    pub fn set_fail_loading_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &LoadError),
+        F: 'static,
+    {
+        // Always registered, the load state depends on it.
+        self.state.callbacks.fail_loading.set(Box::new(callback));
+    }
+
+    /// Set callback for when the JavaScript window object is reset for a new page load.
+    ///
+    /// Runs before any script of the page, the earliest time to add bindings. The DOM might not
+    /// be loaded yet, use `set_dom_ready_callback` for that. Not called for pages without scripts.
+    pub fn set_window_object_ready_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &FrameEvent),
+        F: 'static,
+    {
+        self.state
+            .callbacks
+            .window_object_ready
+            .set(Box::new(callback));
+        unsafe {
+            ulViewSetWindowObjectReadyCallback(
+                self.inner(),
+                Some(window_object_ready),
+                self.user_data(),
+            );
+        }
+    }
+
+    /// Set callback for when the DOM of a frame is loaded and its scripts are parsed.
+    pub fn set_dom_ready_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>, &FrameEvent),
+        F: 'static,
+    {
+        // Always registered, the load state depends on it.
+        self.state.callbacks.dom_ready.set(Box::new(callback));
+    }
+
+    /// Set callback for when the back/forward history changes.
+    pub fn set_update_history_callback<F>(&self, callback: F)
+    where
+        F: FnMut(ViewRef<'_>),
+        F: 'static,
+    {
+        self.state.callbacks.update_history.set(Box::new(callback));
+        unsafe {
+            ulViewSetUpdateHistoryCallback(self.inner(), Some(update_history), self.user_data());
+        }
+    }
+}
+
+/// Register the callbacks every view needs.
+pub(super) unsafe fn register_callbacks(view: ULView, user_data: *mut c_void) {
+    ulViewSetBeginLoadingCallback(view, Some(begin_loading), user_data);
+    ulViewSetFinishLoadingCallback(view, Some(finish_loading), user_data);
+    ulViewSetFailLoadingCallback(view, Some(fail_loading), user_data);
+    ulViewSetDOMReadyCallback(view, Some(dom_ready), user_data);
+}
+
+/// Register the callbacks feeding `ViewRef::events`.
+pub(super) unsafe fn register_event_callbacks(view: ULView, user_data: *mut c_void) {
+    ulViewSetChangeTitleCallback(view, Some(change_title), user_data);
+    ulViewSetChangeURLCallback(view, Some(change_url), user_data);
+    ulViewSetChangeCursorCallback(view, Some(change_cursor), user_data);
+    ulViewSetAddConsoleMessageCallback(view, Some(console), user_data);
+}
+
+/// Make sure Ultralight doesn't call into a view that is being destroyed.
+pub(super) unsafe fn unregister_callbacks(view: ULView) {
+    let null = std::ptr::null_mut();
+    ulViewSetChangeTitleCallback(view, None, null);
+    ulViewSetChangeURLCallback(view, None, null);
+    ulViewSetChangeTooltipCallback(view, None, null);
+    ulViewSetChangeCursorCallback(view, None, null);
+    ulViewSetAddConsoleMessageCallback(view, None, null);
+    ulViewSetBeginLoadingCallback(view, None, null);
+    ulViewSetFinishLoadingCallback(view, None, null);
+    ulViewSetFailLoadingCallback(view, None, null);
+    ulViewSetWindowObjectReadyCallback(view, None, null);
+    ulViewSetDOMReadyCallback(view, None, null);
+    ulViewSetUpdateHistoryCallback(view, None, null);
+}
+
+unsafe extern "C" fn change_title(user_data: *mut c_void, _caller: ULView, title: ULString) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let title = read_ulstring(title);
+    if state.wakers.has_event_streams() {
+        state
+            .wakers
+            .send_event(ViewEvent::TitleChanged(title.clone()));
+    }
+    state
+        .callbacks
+        .change_title
+        .call(|callback| callback(view, &title));
+}
+
+unsafe extern "C" fn change_url(user_data: *mut c_void, _caller: ULView, url: ULString) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let url = read_ulstring(url);
+    if state.wakers.has_event_streams() {
+        state.wakers.send_event(ViewEvent::UrlChanged(url.clone()));
+    }
+    state
+        .callbacks
+        .change_url
+        .call(|callback| callback(view, &url));
+}
+
+unsafe extern "C" fn change_tooltip(user_data: *mut c_void, _caller: ULView, tooltip: ULString) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let tooltip = read_ulstring(tooltip);
+    state
+        .callbacks
+        .change_tooltip
+        .call(|callback| callback(view, &tooltip));
+}
+
+unsafe extern "C" fn change_cursor(user_data: *mut c_void, _caller: ULView, cursor: ULCursor) {
+    let Some(cursor) = Cursor::from_raw(cursor) else {
+        return;
+    };
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    if state.wakers.has_event_streams() {
+        state.wakers.send_event(ViewEvent::CursorChanged(cursor));
+    }
+    state
+        .callbacks
+        .change_cursor
+        .call(|callback| callback(view, cursor));
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn console(
+    user_data: *mut c_void,
+    _caller: ULView,
+    source: ULMessageSource,
+    level: ULMessageLevel,
+    message: ULString,
+    line_number: c_uint,
+    column_number: c_uint,
+    source_id: ULString,
+) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let message = ConsoleMessage {
+        source: MessageSource::from_raw(source),
+        level: MessageLevel::from(level),
+        message: read_ulstring(message),
+        line: line_number,
+        column: column_number,
+        source_url: read_ulstring(source_id),
+    };
+    if state.wakers.has_event_streams() {
+        state.wakers.send_event(ViewEvent::Console(message.clone()));
+    }
+    state
+        .callbacks
+        .console
+        .call(|callback| callback(view, &message));
+}
+
+unsafe extern "C" fn begin_loading(
+    user_data: *mut c_void,
+    _caller: ULView,
+    frame_id: c_ulonglong,
+    is_main_frame: bool,
+    url: ULString,
+) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let frame = FrameEvent::new(frame_id, is_main_frame, url);
+    state.load.borrow_mut().begin_loading(&frame);
+    state
+        .callbacks
+        .begin_loading
+        .call(|callback| callback(view, &frame));
+}
+
+unsafe extern "C" fn finish_loading(
+    user_data: *mut c_void,
+    _caller: ULView,
+    frame_id: c_ulonglong,
+    is_main_frame: bool,
+    url: ULString,
+) {
+    #[cfg(feature = "filewatching")]
+    {
+        *ASSETS_MODIFIED.write().unwrap() = false;
+    }
+
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let frame = FrameEvent::new(frame_id, is_main_frame, url);
+    state.load.borrow_mut().finish_loading(&frame);
+    state
+        .callbacks
+        .finish_loading
+        .call(|callback| callback(view, &frame));
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn fail_loading(
+    user_data: *mut c_void,
+    _caller: ULView,
+    frame_id: c_ulonglong,
+    is_main_frame: bool,
+    url: ULString,
+    description: ULString,
+    error_domain: ULString,
+    error_code: c_int,
+) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let error = LoadError {
+        frame: FrameEvent::new(frame_id, is_main_frame, url),
+        description: read_ulstring(description),
+        error_domain: read_ulstring(error_domain),
+        error_code,
+    };
+    state.load.borrow_mut().fail_loading(&error);
+    state
+        .callbacks
+        .fail_loading
+        .call(|callback| callback(view, &error));
+}
+
+unsafe extern "C" fn window_object_ready(
+    user_data: *mut c_void,
+    _caller: ULView,
+    frame_id: c_ulonglong,
+    is_main_frame: bool,
+    url: ULString,
+) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let frame = FrameEvent::new(frame_id, is_main_frame, url);
+    state
+        .callbacks
+        .window_object_ready
+        .call(|callback| callback(view, &frame));
+}
+
+unsafe extern "C" fn dom_ready(
+    user_data: *mut c_void,
+    _caller: ULView,
+    frame_id: c_ulonglong,
+    is_main_frame: bool,
+    url: ULString,
+) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    let frame = FrameEvent::new(frame_id, is_main_frame, url);
+    state.load.borrow_mut().dom_ready(&frame);
+    state
+        .callbacks
+        .dom_ready
+        .call(|callback| callback(view, &frame));
+}
+
+unsafe extern "C" fn update_history(user_data: *mut c_void, _caller: ULView) {
+    let view = ViewRef::from_user_data(user_data);
+    let state = view.state.clone();
+    state
+        .callbacks
+        .update_history
+        .call(|callback| callback(view));
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+use super::ViewRef;
+use crate::sys::{
+    ULMessageLevel, ULMessageSource, ULMessageSource_kMessageSource_AppCache,
+    ULMessageSource_kMessageSource_CSS, ULMessageSource_kMessageSource_ConsoleAPI,
+    ULMessageSource_kMessageSource_ContentBlocker, ULMessageSource_kMessageSource_JS,
+    ULMessageSource_kMessageSource_Network, ULMessageSource_kMessageSource_Rendering,
+    ULMessageSource_kMessageSource_Security, ULMessageSource_kMessageSource_Storage,
+    ULMessageSource_kMessageSource_XML,
+};
+
+/// Part of the engine a `ConsoleMessage` comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MessageSource {
+    Xml,
+    /// Uncaught JavaScript errors and warnings.
+    JavaScript,
+    Network,
+    /// Calls to `console.log` and friends.
+    ConsoleApi,
+    Storage,
+    AppCache,
+    Rendering,
+    Css,
+    Security,
+    ContentBlocker,
+    Other,
+}
+
+impl MessageSource {
+    pub(crate) fn from_raw(value: ULMessageSource) -> Self {
+        if value == ULMessageSource_kMessageSource_XML {
+            Self::Xml
+        } else if value == ULMessageSource_kMessageSource_JS {
+            Self::JavaScript
+        } else if value == ULMessageSource_kMessageSource_Network {
+            Self::Network
+        } else if value == ULMessageSource_kMessageSource_ConsoleAPI {
+            Self::ConsoleApi
+        } else if value == ULMessageSource_kMessageSource_Storage {
+            Self::Storage
+        } else if value == ULMessageSource_kMessageSource_AppCache {
+            Self::AppCache
+        } else if value == ULMessageSource_kMessageSource_Rendering {
+            Self::Rendering
+        } else if value == ULMessageSource_kMessageSource_CSS {
+            Self::Css
+        } else if value == ULMessageSource_kMessageSource_Security {
+            Self::Security
+        } else if value == ULMessageSource_kMessageSource_ContentBlocker {
+            Self::ContentBlocker
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Severity of a `ConsoleMessage`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MessageLevel {
+    Log,
+    Warning,
+    Error,
+    Debug,
+    Info,
+}
+
+impl From<ULMessageLevel> for MessageLevel {
+    fn from(value: ULMessageLevel) -> Self {
+        match value {
+            ULMessageLevel::kMessageLevel_Log => Self::Log,
+            ULMessageLevel::kMessageLevel_Warning => Self::Warning,
+            ULMessageLevel::kMessageLevel_Error => Self::Error,
+            ULMessageLevel::kMessageLevel_Debug => Self::Debug,
+            ULMessageLevel::kMessageLevel_Info => Self::Info,
+        }
+    }
+}
+
+/// Message added to the console of a view, see `ViewRef::set_console_callback`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    pub source: MessageSource,
+    pub level: MessageLevel,
+    pub message: String,
+    /// 1-based line in `source_url`, 0 when unknown.
+    pub line: u32,
+    /// 1-based column in `source_url`, 0 when unknown.
+    pub column: u32,
+    /// URL of the script or document the message comes from, empty when unknown.
+    pub source_url: String,
+}
+
+/// Formats as `url:line:column: message`, leaving out what is unknown.
+impl fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.source_url.is_empty() {
+            write!(f, "{}:", self.source_url)?;
+            if self.line > 0 {
+                write!(f, "{}:", self.line)?;
+                if self.column > 0 {
+                    write!(f, "{}:", self.column)?;
+                }
+            }
+            f.write_str(" ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Console callback that forwards messages to the `log` crate.
+///
+/// Records use the target `ultralight::console`, with `source_url` and `line` as their file and
+/// line. Errors and warnings keep their level, the rest is logged as info or debug.
+///
+/// ```ignore
+/// view.set_console_callback(ultralight::log_console_message);
+/// ```
+#[cfg(feature = "log")]
+pub fn log_console_message(_view: ViewRef<'_>, message: &ConsoleMessage) {
+    let level = match message.level {
+        MessageLevel::Error => log::Level::Error,
+        MessageLevel::Warning => log::Level::Warn,
+        MessageLevel::Log | MessageLevel::Info => log::Level::Info,
+        MessageLevel::Debug => log::Level::Debug,
+    };
+    // Filtered like the `log!` macros do, the record is built by hand to carry the location.
+    if level > log::STATIC_MAX_LEVEL || level > log::max_level() {
+        return;
+    }
+
+    log::logger().log(
+        &log::Record::builder()
+            .args(format_args!("{}", message))
+            .level(level)
+            .target("ultralight::console")
+            .file(Some(message.source_url.as_str()).filter(|url| !url.is_empty()))
+            .line(Some(message.line).filter(|&line| line > 0))
+            .build(),
+    );
+}
+
+/// Console callback that emits messages as `tracing` events.
+///
+/// Events use the target `ultralight::console`, with the location in the `source_url`, `line`
+/// and `column` fields. Levels are mapped like `log_console_message` does.
+#[cfg(feature = "tracing")]
+pub fn trace_console_message(_view: ViewRef<'_>, message: &ConsoleMessage) {
+    macro_rules! event {
+        ($level:expr) => {
+            tracing::event!(
+                target: "ultralight::console",
+                $level,
+                source = ?message.source,
+                source_url = %message.source_url,
+                line = message.line,
+                column = message.column,
+                "{}",
+                message.message
+            )
+        };
+    }
+
+    match message.level {
+        MessageLevel::Error => event!(tracing::Level::ERROR),
+        MessageLevel::Warning => event!(tracing::Level::WARN),
+        MessageLevel::Log | MessageLevel::Info => event!(tracing::Level::INFO),
+        MessageLevel::Debug => event!(tracing::Level::DEBUG),
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    future::Future,
+    pin::{pin, Pin},
+    rc::{Rc, Weak},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
+
+use super::{
+    callbacks::register_event_callbacks, load::UPDATE_INTERVAL, ConsoleMessage, Cursor, LoadError,
+    LoadState, Renderer, RendererHandle, ViewRef,
+};
+
+/// Something that happened to a view, see `ViewRef::events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViewEvent {
+    TitleChanged(String),
+    UrlChanged(String),
+    CursorChanged(Cursor),
+    Console(ConsoleMessage),
+}
+
+/// Events buffered for one `ViewEvents`.
+#[derive(Default)]
+struct EventQueue {
+    events: RefCell<VecDeque<ViewEvent>>,
+    waker: RefCell<Option<Waker>>,
+    /// Set once the view is destroyed.
+    closed: Cell<bool>,
+}
+
+impl EventQueue {
+    fn push(&self, event: ViewEvent) {
+        self.events.borrow_mut().push_back(event);
+        self.wake();
+    }
+
+    fn close(&self) {
+        self.closed.set(true);
+        self.wake();
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Wakers of the futures waiting for a view to load, and the queues of its event streams.
+#[derive(Default)]
+pub(super) struct ViewWakers {
+    load: RefCell<Vec<Waker>>,
+    events: RefCell<Vec<Weak<EventQueue>>>,
+}
+
+impl ViewWakers {
+    fn register_load(&self, waker: &Waker) {
+        let mut wakers = self.load.borrow_mut();
+        if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Whether any `ViewEvents` is listening, so events aren't built for nobody.
+    pub(super) fn has_event_streams(&self) -> bool {
+        !self.events.borrow().is_empty()
+    }
+
+    pub(super) fn send_event(&self, event: ViewEvent) {
+        self.events
+            .borrow_mut()
+            .retain(|queue| queue.strong_count() > 0);
+        let queues: Vec<_> = self
+            .events
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        for queue in queues {
+            queue.push(event.clone());
+        }
+    }
+
+    /// Called when the view is destroyed, ends its event streams.
+    pub(super) fn close(&self) {
+        let queues = self.events.take();
+        for queue in queues.iter().filter_map(Weak::upgrade) {
+            queue.close();
+        }
+    }
+}
+
+impl RendererHandle {
+    /// Poll the load futures again after `Renderer::update`, it is what moves them forward.
+    pub(super) fn wake_load_futures(&self) {
+        let views: Vec<_> = self
+            .views
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        for view in views {
+            let wakers = view.wakers.load.take();
+            wakers.into_iter().for_each(Waker::wake);
+        }
+    }
+}
+
+/// Resolves once a view finished loading, see `ViewRef::load_url_async`.
+pub struct LoadFuture<'a> {
+    view: ViewRef<'a>,
+}
+
+impl Future for LoadFuture<'_> {
+    type Output = Result<(), LoadError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.view.load_state() {
+            LoadState::Failed(error) => Poll::Ready(Err(error)),
+            _ if self.view.is_ready() => Poll::Ready(Ok(())),
+            _ => {
+                self.view.state.wakers.register_load(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Resolves once the DOM of a view's main frame is loaded, see `ViewRef::dom_ready`.
+pub struct DomReadyFuture<'a> {
+    view: ViewRef<'a>,
+}
+
+impl Future for DomReadyFuture<'_> {
+    type Output = Result<(), LoadError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.view.load_state() {
+            LoadState::Failed(error) => Poll::Ready(Err(error)),
+            LoadState::DomReady | LoadState::Loaded => Poll::Ready(Ok(())),
+            LoadState::Idle | LoadState::Loading => {
+                self.view.state.wakers.register_load(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Stream of the events of a view, see `ViewRef::events`.
+///
+/// Implements `futures_core::Stream` with the `futures` feature. Ends when the view is destroyed.
+pub struct ViewEvents {
+    queue: Rc<EventQueue>,
+}
+
+impl ViewEvents {
+    /// Wait for the next event, `None` once the view is destroyed.
+    pub async fn next(&mut self) -> Option<ViewEvent> {
+        std::future::poll_fn(|cx| self.poll_event(cx)).await
+    }
+
+    /// Take the next event without waiting.
+    pub fn try_next(&mut self) -> Option<ViewEvent> {
+        self.queue.events.borrow_mut().pop_front()
+    }
+
+    fn poll_event(&self, cx: &mut Context<'_>) -> Poll<Option<ViewEvent>> {
+        if let Some(event) = self.queue.events.borrow_mut().pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if self.queue.closed.get() {
+            return Poll::Ready(None);
+        }
+        *self.queue.waker.borrow_mut() = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for ViewEvents {
+    type Item = ViewEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ViewEvent>> {
+        self.poll_event(cx)
+    }
+}
+
+impl<'a> ViewRef<'a> {
+    /// Load a URL into the main frame, resolving once it is loaded.
+    ///
+    /// Like `Renderer::wait_for_load` it only makes progress while `Renderer::update` is called,
+    /// through `Renderer::run` or `Renderer::block_on`.
+    pub fn load_url_async(&self, url: String) -> LoadFuture<'a> {
+        self.load_url(url);
+        LoadFuture {
+            view: ViewRef::new(self.state.clone()),
+        }
+    }
+
+    /// Resolves once the DOM of the main frame is loaded and its scripts are parsed.
+    ///
+    /// Resolves right away when the current page got that far already.
+    pub fn dom_ready(&self) -> DomReadyFuture<'a> {
+        DomReadyFuture {
+            view: ViewRef::new(self.state.clone()),
+        }
+    }
+
+    /// Stream of title, URL and cursor changes and console messages.
+    ///
+    /// Every stream gets all events from now on, independent of the callbacks set on the view.
+    pub fn events(&self) -> ViewEvents {
+        let queue = Rc::new(EventQueue::default());
+        self.state
+            .wakers
+            .events
+            .borrow_mut()
+            .push(Rc::downgrade(&queue));
+        unsafe { register_event_callbacks(self.inner(), self.user_data()) };
+        ViewEvents { queue }
+    }
+}
+
+/// Does nothing, `Renderer::block_on` polls after every update anyway.
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+impl Renderer {
+    /// Call `update` every `interval` from an async runtime, it never completes.
+    ///
+    /// `sleep` is the timer of the runtime, like `tokio::time::sleep`. The futures of views only
+    /// make progress while this runs. Ultralight is single threaded, so run it next to them on a
+    /// local executor, like a tokio `LocalSet`:
+    ///
+    /// ```ignore
+    /// let local = tokio::task::LocalSet::new();
+    /// local
+    ///     .run_until(async {
+    ///         let view = renderer.create_view(800, 600, &ViewConfig::default());
+    ///         tokio::select! {
+    ///             _ = renderer.run(Duration::from_millis(4), tokio::time::sleep) => unreachable!(),
+    ///             result = view.load_url_async("https://ultralig.ht".to_owned()) => result,
+    ///         }
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn run<S, F>(&mut self, interval: Duration, mut sleep: S)
+    where
+        S: FnMut(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        loop {
+            self.update();
+            sleep(interval).await;
+        }
+    }
+
+    /// Run `future` to completion on this thread, calling `update` in between polls.
+    ///
+    /// Blocks the thread, use `run` from an async runtime.
+    pub fn block_on<F: Future>(&mut self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            self.update();
+            std::thread::sleep(UPDATE_INTERVAL);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    time::{Duration, Instant},
+};
+
+use super::{FrameEvent, LoadError, Renderer, ViewRef};
+
+/// Time `Renderer::run_until` and `Renderer::block_on` sleep between updates.
+pub(super) const UPDATE_INTERVAL: Duration = Duration::from_millis(4);
+
+/// Oldest events are dropped past this, so a view nobody polls doesn't grow forever.
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+/// How far a view or frame got loading its current URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    /// Nothing was loaded yet.
+    Idle,
+    Loading,
+    /// The DOM is loaded and scripts are parsed, resources might still be loading.
+    DomReady,
+    Loaded,
+    Failed(LoadError),
+}
+
+/// Loading progress of a frame, see `ViewRef::take_load_events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadEvent {
+    BeginLoading(FrameEvent),
+    DomReady(FrameEvent),
+    FinishLoading(FrameEvent),
+    FailLoading(LoadError),
+}
+
+impl LoadEvent {
+    pub fn frame(&self) -> &FrameEvent {
+        match self {
+            Self::BeginLoading(frame) | Self::DomReady(frame) | Self::FinishLoading(frame) => frame,
+            Self::FailLoading(error) => &error.frame,
+        }
+    }
+}
+
+/// Load states of a view and its frames, updated from the loading callbacks.
+pub(super) struct LoadTracker {
+    main_frame: LoadState,
+    frames: HashMap<u64, LoadState>,
+    events: VecDeque<LoadEvent>,
+}
+
+impl Default for LoadTracker {
+    fn default() -> Self {
+        Self {
+            main_frame: LoadState::Idle,
+            frames: HashMap::new(),
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl LoadTracker {
+    pub(super) fn main_frame(&self) -> &LoadState {
+        &self.main_frame
+    }
+
+    pub(super) fn frame(&self, frame_id: u64) -> Option<&LoadState> {
+        self.frames.get(&frame_id)
+    }
+
+    pub(super) fn take_events(&mut self) -> Vec<LoadEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// A new URL is about to be loaded into the main frame, before Ultralight reports it.
+    pub(super) fn start(&mut self) {
+        self.main_frame = LoadState::Loading;
+        self.frames.clear();
+    }
+
+    pub(super) fn begin_loading(&mut self, frame: &FrameEvent) {
+        if frame.is_main_frame {
+            // The subframes of the previous document are gone.
+            self.start();
+        }
+        self.set(frame, LoadState::Loading);
+        self.push(LoadEvent::BeginLoading(frame.clone()));
+    }
+
+    pub(super) fn dom_ready(&mut self, frame: &FrameEvent) {
+        self.set(frame, LoadState::DomReady);
+        self.push(LoadEvent::DomReady(frame.clone()));
+    }
+
+    pub(super) fn finish_loading(&mut self, frame: &FrameEvent) {
+        // A failed load is finished as well, keep the error.
+        if !matches!(self.frame(frame.frame_id), Some(LoadState::Failed(_))) {
+            self.set(frame, LoadState::Loaded);
+        }
+        self.push(LoadEvent::FinishLoading(frame.clone()));
+    }
+
+    pub(super) fn fail_loading(&mut self, error: &LoadError) {
+        self.set(&error.frame, LoadState::Failed(error.clone()));
+        self.push(LoadEvent::FailLoading(error.clone()));
+    }
+
+    fn set(&mut self, frame: &FrameEvent, state: LoadState) {
+        if frame.is_main_frame {
+            self.main_frame = state.clone();
+        }
+        self.frames.insert(frame.frame_id, state);
+    }
+
+    fn push(&mut self, event: LoadEvent) {
+        if self.events.len() == MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Why `Renderer::wait_for_load` returned before the view was loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitError {
+    /// Loading the main frame failed.
+    Failed(LoadError),
+    TimedOut,
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(error) => error.fmt(f),
+            Self::TimedOut => f.write_str("timed out waiting for the view to load"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Failed(error) => Some(error),
+            Self::TimedOut => None,
+        }
+    }
+}
+
+impl Renderer {
+    /// Call `update` until `predicate` returns true, sleeping a few milliseconds in between.
+    ///
+    /// Returns false when `timeout` passed first.
+    pub fn run_until<F>(&mut self, mut predicate: F, timeout: Duration) -> bool
+    where
+        F: FnMut() -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.update();
+            if predicate() {
+                return true;
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            std::thread::sleep(UPDATE_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Call `update` until the main frame of `view` and its subframes are loaded.
+    ///
+    /// Times out when nothing was loaded into `view`.
+    pub fn wait_for_load(
+        &mut self,
+        view: &ViewRef<'_>,
+        timeout: Duration,
+    ) -> Result<(), WaitError> {
+        let mut failed = None;
+        let done = self.run_until(
+            || match view.load_state() {
+                LoadState::Failed(error) => {
+                    failed = Some(error);
+                    true
+                }
+                _ => view.is_ready(),
+            },
+            timeout,
+        );
+
+        match failed {
+            Some(error) => Err(WaitError::Failed(error)),
+            None if done => Ok(()),
+            None => Err(WaitError::TimedOut),
+        }
+    }
+}
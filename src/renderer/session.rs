@@ -0,0 +1,148 @@
+use std::{ffi::CString, io, path::PathBuf, rc::Rc};
+
+use super::RendererHandle;
+use crate::{
+    platform::read_ulstring,
+    sys::{
+        ulCreateSession, ulCreateString, ulDefaultSession, ulDestroySession, ulDestroyString,
+        ulSessionGetDiskPath, ulSessionGetId, ulSessionGetName, ulSessionIsPersistent, ULSession,
+    },
+};
+
+struct SessionHandle {
+    inner: ULSession,
+    /// Sessions can't outlive the renderer they were created by.
+    renderer: Rc<RendererHandle>,
+    /// The default session belongs to the renderer and must not be destroyed.
+    owned: bool,
+}
+
+impl Drop for SessionHandle {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe { ulDestroySession(self.inner) };
+        }
+    }
+}
+
+/// Where views store cookies, local storage, caches and other site data.
+///
+/// Views created in different sessions don't share any of it. Persistent sessions keep their
+/// data on disk, under a directory named after the session in `Config::set_cache_path`.
+/// Cloning is cheap, the session is destroyed once the last clone and view using it is gone.
+#[derive(Clone)]
+pub struct Session {
+    handle: Rc<SessionHandle>,
+}
+
+impl Session {
+    pub(super) fn new(renderer: &Rc<RendererHandle>, persistent: bool, name: &str) -> Self {
+        let name = CString::new(name).unwrap();
+        let inner = unsafe {
+            let name = ulCreateString(name.as_ptr());
+            let session = ulCreateSession(renderer.inner, persistent, name);
+            ulDestroyString(name);
+            session
+        };
+
+        Self {
+            handle: Rc::new(SessionHandle {
+                inner,
+                renderer: renderer.clone(),
+                owned: true,
+            }),
+        }
+    }
+
+    pub(super) fn renderer_default(renderer: &Rc<RendererHandle>) -> Self {
+        Self {
+            handle: Rc::new(SessionHandle {
+                inner: unsafe { ulDefaultSession(renderer.inner) },
+                renderer: renderer.clone(),
+                owned: false,
+            }),
+        }
+    }
+
+    /// Whether the session's data is stored on disk.
+    pub fn is_persistent(&self) -> bool {
+        unsafe { ulSessionIsPersistent(self.handle.inner) }
+    }
+
+    pub fn name(&self) -> String {
+        read_ulstring(unsafe { ulSessionGetName(self.handle.inner) })
+    }
+
+    /// Numeric id, unique among the sessions of the renderer.
+    pub fn id(&self) -> u64 {
+        unsafe { ulSessionGetId(self.handle.inner) }
+    }
+
+    /// Directory the session stores its data in, `None` when it isn't persistent.
+    pub fn disk_path(&self) -> Option<PathBuf> {
+        if !self.is_persistent() {
+            return None;
+        }
+
+        let path = read_ulstring(unsafe { ulSessionGetDiskPath(self.handle.inner) });
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    }
+
+    /// Delete everything the session stored on disk.
+    ///
+    /// Fails when a view still uses the session, Ultralight keeps its files open while it does.
+    /// Does nothing for sessions that aren't persistent.
+    pub fn clear_disk_data(&self) -> io::Result<()> {
+        if self.view_count() > 0 {
+            return Err(io::Error::other("Session is still used by a view"));
+        }
+
+        match self.disk_path() {
+            Some(path) => match std::fs::remove_dir_all(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Number of views alive in this session.
+    pub fn view_count(&self) -> usize {
+        self.handle
+            .renderer
+            .views
+            .borrow()
+            .iter()
+            .filter_map(|view| view.upgrade())
+            .filter(|view| view.session == *self)
+            .count()
+    }
+
+    pub(super) fn raw(&self) -> ULSession {
+        self.handle.inner
+    }
+}
+
+impl PartialEq for Session {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle.inner == other.handle.inner
+    }
+}
+
+impl Eq for Session {}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("name", &self.name())
+            .field("id", &self.id())
+            .field("persistent", &self.is_persistent())
+            .finish()
+    }
+}
+
+impl From<&Session> for ULSession {
+    fn from(value: &Session) -> Self {
+        value.raw()
+    }
+}
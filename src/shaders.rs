@@ -0,0 +1,120 @@
+use crate::{
+    gpu_driver::{GpuState, ShaderType},
+    sys::{ulApplyProjection, ULGPUState, ULMatrix4x4},
+};
+
+// The WGSL files are the source, the GLSL and SPIR-V next to them are generated from them by
+// building with the `generate_shaders` feature.
+//
+// All versions use the same interface: `Uniforms` in group 0 binding 0, `texture0` and `texture1`
+// in group 1 binding 0 and 1 and a filtering sampler in group 1 binding 2. GLSL combines the
+// sampler into the textures, which use texture units 0 and 1. Vertex inputs use the locations of
+// `Vertex2f4ub2f::ATTRIBUTES` and `Vertex2f4ub2f2f28f::ATTRIBUTES`.
+
+/// Fill shader in WGSL, entry points are `vs_main` and `fs_main`.
+pub const FILL_WGSL: &str = concat!(
+    include_str!("shaders/common.wgsl"),
+    include_str!("shaders/fill.wgsl")
+);
+/// FillPath shader in WGSL, entry points are `vs_main` and `fs_main`.
+pub const FILL_PATH_WGSL: &str = concat!(
+    include_str!("shaders/common.wgsl"),
+    include_str!("shaders/fill_path.wgsl")
+);
+
+/// Fill vertex shader in GLSL 450.
+pub const FILL_VERTEX_GLSL: &str = include_str!("shaders/fill.vert");
+/// Fill fragment shader in GLSL 450.
+pub const FILL_FRAGMENT_GLSL: &str = include_str!("shaders/fill.frag");
+/// FillPath vertex shader in GLSL 450.
+pub const FILL_PATH_VERTEX_GLSL: &str = include_str!("shaders/fill_path.vert");
+/// FillPath fragment shader in GLSL 450.
+pub const FILL_PATH_FRAGMENT_GLSL: &str = include_str!("shaders/fill_path.frag");
+
+/// Fill shader as a little-endian SPIR-V module, entry points are `vs_main` and `fs_main`.
+pub const FILL_SPIRV: &[u8] = include_bytes!("shaders/fill.spv");
+/// FillPath shader as a little-endian SPIR-V module, entry points are `vs_main` and `fs_main`.
+pub const FILL_PATH_SPIRV: &[u8] = include_bytes!("shaders/fill_path.spv");
+
+/// Uniform buffer of both shaders, laid out to match the `std140` block they declare.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct Uniforms {
+    /// Time, viewport width, viewport height and screen scale.
+    pub state: [f32; 4],
+    /// `GpuState::transform` with the screen-space projection applied.
+    pub transform: [f32; 16],
+    pub scalar4: [f32; 8],
+    pub vector: [[f32; 4]; 8],
+    pub clip_size: u32,
+    pub padding: [u32; 3],
+    pub clip: [[f32; 16]; 8],
+}
+
+// Shaders index `clip` at offset 256, the total size is used as minimum binding size.
+const _: () = assert!(std::mem::size_of::<Uniforms>() == Uniforms::SIZE as usize);
+
+impl Uniforms {
+    pub const SIZE: u64 = 768;
+
+    /// Raw bytes of the uniforms, ready to be uploaded.
+    pub fn as_bytes(&self) -> &[u8] {
+        // `Uniforms` is plain `repr(C)` floats and integers without implicit padding.
+        unsafe {
+            std::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                std::mem::size_of::<Self>(),
+            )
+        }
+    }
+}
+
+impl Uniforms {
+    /// Uniforms of a draw, `scale` is the device scale of the view and `time` is in seconds.
+    pub fn new(state: &GpuState, scale: f32, time: f32) -> Self {
+        let transform = unsafe {
+            ulApplyProjection(
+                ULMatrix4x4 {
+                    data: state.transform,
+                },
+                state.viewport_width as f32,
+                state.viewport_height as f32,
+                false,
+            )
+        };
+
+        let mut clip = [[0.0; 16]; 8];
+        clip[..state.clip().len()].copy_from_slice(state.clip());
+
+        Self {
+            state: [
+                time,
+                state.viewport_width as f32,
+                state.viewport_height as f32,
+                scale,
+            ],
+            transform: transform.data,
+            scalar4: state.uniform_scalar,
+            vector: state.uniform_vector,
+            clip_size: state.clip().len() as u32,
+            padding: [0; 3],
+            clip,
+        }
+    }
+}
+
+/// Uniforms at a device scale of 1 and a time of 0, use `Uniforms::new` for scaled views.
+impl From<&GpuState> for Uniforms {
+    fn from(state: &GpuState) -> Self {
+        Self::new(state, 1.0, 0.0)
+    }
+}
+
+/// Uniforms at a device scale of 1 and a time of 0, use `Uniforms::new` for scaled views.
+impl From<&ULGPUState> for Uniforms {
+    fn from(state: &ULGPUState) -> Self {
+        // The uniforms are the same for every shader.
+        Self::from(&GpuState::new(state, ShaderType::Fill))
+    }
+}
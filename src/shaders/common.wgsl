@@ -0,0 +1,158 @@
+// Shared by `fill.wgsl` and `fill_path.wgsl`, a port of `examples/shaders/*.hlsl`.
+//
+// Bindings differ from the HLSL so the uniforms can use a dynamic offset:
+// group 0 holds the uniforms, group 1 the two textures and the sampler.
+
+struct Uniforms {
+    state: vec4<f32>,
+    transform: mat4x4<f32>,
+    scalar4: array<vec4<f32>, 2>,
+    vector: array<vec4<f32>, 8>,
+    clip_size: u32,
+    clip: array<mat4x4<f32>, 8>,
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(1) @binding(0) var texture0: texture_2d<f32>;
+@group(1) @binding(1) var texture1: texture_2d<f32>;
+@group(1) @binding(2) var sampler0: sampler;
+
+fn scalar(i: u32) -> f32 {
+    return u.scalar4[i / 4u][i % 4u];
+}
+
+fn sd_rect(p: vec2<f32>, size: vec2<f32>) -> f32 {
+    let d = abs(p) - size;
+    return min(max(d.x, d.y), 0.0) + length(max(d, vec2<f32>(0.0)));
+}
+
+// The below function "sd_ellipse" is MIT licensed with following text:
+//
+// The MIT License
+// Copyright 2013 Inigo Quilez
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following conditions: The above copyright
+// notice and this permission notice shall be included in all copies or substantial
+// portions of the Software. THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO
+// EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR
+// OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+fn sd_ellipse(p_in: vec2<f32>, ab_in: vec2<f32>) -> f32 {
+    if (abs(ab_in.x - ab_in.y) < 0.1) {
+        return length(p_in) - ab_in.x;
+    }
+
+    var p = abs(p_in);
+    var ab = ab_in;
+    if (p.x > p.y) {
+        p = p.yx;
+        ab = ab.yx;
+    }
+
+    let l = ab.y * ab.y - ab.x * ab.x;
+
+    let m = ab.x * p.x / l;
+    let n = ab.y * p.y / l;
+    let m2 = m * m;
+    let n2 = n * n;
+
+    let c = (m2 + n2 - 1.0) / 3.0;
+    let c3 = c * c * c;
+
+    let q = c3 + m2 * n2 * 2.0;
+    let d = c3 + m2 * n2;
+    let g = m + m * n2;
+
+    var co: f32;
+
+    if (d < 0.0) {
+        let h = acos(q / c3) / 3.0;
+        let s = cos(h);
+        let t = sin(h) * sqrt(3.0);
+        let rx = sqrt(-c * (s + t + 2.0) + m2);
+        let ry = sqrt(-c * (s - t + 2.0) + m2);
+        co = (ry + sign(l) * rx + abs(g) / (rx * ry) - m) / 2.0;
+    } else {
+        let h = 2.0 * m * n * sqrt(d);
+        let s = sign(q + h) * pow(abs(q + h), 1.0 / 3.0);
+        let t = sign(q - h) * pow(abs(q - h), 1.0 / 3.0);
+        let rx = -s - t - c * 4.0 + 2.0 * m2;
+        let ry = (s - t) * sqrt(3.0);
+        let rm = sqrt(rx * rx + ry * ry);
+        let k = ry / sqrt(rm - rx);
+        co = (k + 2.0 * g / rm - m) / 2.0;
+    }
+
+    let si = sqrt(1.0 - co * co);
+
+    let r = vec2<f32>(ab.x * co, ab.y * si);
+
+    return length(r - p) * sign(p.y - r.y);
+}
+
+fn sd_round_rect(p: vec2<f32>, size_in: vec2<f32>, rx: vec4<f32>, ry: vec4<f32>) -> f32 {
+    let size = size_in * 0.5;
+    var corner: vec2<f32>;
+
+    corner = vec2<f32>(-size.x + rx.x, -size.y + ry.x); // Top-Left
+    if (rx.x * ry.x > 0.0 && p.x < corner.x && p.y <= corner.y) {
+        return sd_ellipse(p - corner, vec2<f32>(rx.x, ry.x));
+    }
+
+    corner = vec2<f32>(size.x - rx.y, -size.y + ry.y); // Top-Right
+    if (rx.y * ry.y > 0.0 && p.x >= corner.x && p.y <= corner.y) {
+        return sd_ellipse(p - corner, vec2<f32>(rx.y, ry.y));
+    }
+
+    corner = vec2<f32>(size.x - rx.z, size.y - ry.z); // Bottom-Right
+    if (rx.z * ry.z > 0.0 && p.x >= corner.x && p.y >= corner.y) {
+        return sd_ellipse(p - corner, vec2<f32>(rx.z, ry.z));
+    }
+
+    corner = vec2<f32>(-size.x + rx.w, size.y - ry.w); // Bottom-Left
+    if (rx.w * ry.w > 0.0 && p.x < corner.x && p.y > corner.y) {
+        return sd_ellipse(p - corner, vec2<f32>(rx.w, ry.w));
+    }
+
+    return sd_rect(p, size);
+}
+
+fn transform_affine(val: vec2<f32>, a: vec2<f32>, b: vec2<f32>, c: vec2<f32>) -> vec2<f32> {
+    return val.x * a + val.y * b + c;
+}
+
+fn antialias2(d: f32) -> f32 {
+    return smoothstep(-0.6180469, 0.6180469, d / fwidth(d));
+}
+
+// Only call from uniform control flow, `fwidth` needs the neighbouring pixels.
+fn apply_clip(obj_coord: vec2<f32>, color: vec4<f32>) -> vec4<f32> {
+    var out_color = color;
+    for (var i = 0u; i < u.clip_size; i++) {
+        let data = u.clip[i];
+        let origin = data[0].xy;
+        let size = data[0].zw;
+        let radii_x = floor(data[1] / 65536.0);
+        let radii_y = floor(data[1] - radii_x * 65536.0);
+        let inverse = data[3].z != 0.0;
+
+        var p = transform_affine(obj_coord, data[2].xy, data[2].zw, data[3].xy);
+        p -= origin;
+        var d_clip = sd_round_rect(p, size, radii_x, radii_y);
+        if (inverse) {
+            d_clip = -d_clip;
+        }
+
+        let alpha = antialias2(-d_clip);
+        out_color = out_color * alpha;
+    }
+    return out_color;
+}
@@ -0,0 +1,714 @@
+#version 450 core
+struct Uniforms {
+    vec4 state;
+    mat4x4 transform;
+    vec4 scalar4_[2];
+    vec4 vector[8];
+    uint clip_size;
+    mat4x4 clip[8];
+};
+struct VertexOutput {
+    vec4 position;
+    vec4 color;
+    vec2 tex_coord;
+    vec2 obj_coord;
+    vec4 data0_;
+    vec4 data1_;
+    vec4 data2_;
+    vec4 data3_;
+    vec4 data4_;
+    vec4 data5_;
+    vec4 data6_;
+};
+struct GradientStop {
+    float percent;
+    vec4 color;
+};
+const float AA_WIDTH = 0.354;
+
+layout(std140, binding = 0) uniform Uniforms_block_0Fragment { Uniforms _group_0_binding_0_fs; };
+
+layout(binding = 0) uniform sampler2D _group_1_binding_0_fs;
+
+layout(binding = 1) uniform sampler2D _group_1_binding_1_fs;
+
+layout(location = 0) smooth in vec4 _vs2fs_location0;
+layout(location = 1) smooth in vec2 _vs2fs_location1;
+layout(location = 2) smooth in vec2 _vs2fs_location2;
+layout(location = 3) smooth in vec4 _vs2fs_location3;
+layout(location = 4) smooth in vec4 _vs2fs_location4;
+layout(location = 5) smooth in vec4 _vs2fs_location5;
+layout(location = 6) smooth in vec4 _vs2fs_location6;
+layout(location = 7) smooth in vec4 _vs2fs_location7;
+layout(location = 8) smooth in vec4 _vs2fs_location8;
+layout(location = 9) smooth in vec4 _vs2fs_location9;
+layout(location = 0) out vec4 _fs2p_location0;
+
+float scalar(uint i) {
+    float _e9 = _group_0_binding_0_fs.scalar4_[(i / 4u)][(i % 4u)];
+    return _e9;
+}
+
+float sd_rect(vec2 p, vec2 size) {
+    vec2 d_4 = (abs(p) - size);
+    return (min(max(d_4.x, d_4.y), 0.0) + length(max(d_4, vec2(0.0))));
+}
+
+float sd_ellipse(vec2 p_in, vec2 ab_in) {
+    vec2 p_1 = vec2(0.0);
+    vec2 ab = vec2(0.0);
+    float co = 0.0;
+    if ((abs((ab_in.x - ab_in.y)) < 0.1)) {
+        return (length(p_in) - ab_in.x);
+    }
+    p_1 = abs(p_in);
+    ab = ab_in;
+    float _e15 = p_1.x;
+    float _e17 = p_1.y;
+    if ((_e15 > _e17)) {
+        vec2 _e19 = p_1;
+        p_1 = _e19.yx;
+        vec2 _e21 = ab;
+        ab = _e21.yx;
+    }
+    float _e24 = ab.y;
+    float _e26 = ab.y;
+    float _e29 = ab.x;
+    float _e31 = ab.x;
+    float l = ((_e24 * _e26) - (_e29 * _e31));
+    float _e35 = ab.x;
+    float _e37 = p_1.x;
+    float m = ((_e35 * _e37) / l);
+    float _e41 = ab.y;
+    float _e43 = p_1.y;
+    float n = ((_e41 * _e43) / l);
+    float m2_ = (m * m);
+    float n2_ = (n * n);
+    float c_2 = (((m2_ + n2_) - 1.0) / 3.0);
+    float c3_ = ((c_2 * c_2) * c_2);
+    float q = (c3_ + ((m2_ * n2_) * 2.0));
+    float d_5 = (c3_ + (m2_ * n2_));
+    float g = (m + (m * n2_));
+    if ((d_5 < 0.0)) {
+        float h = (acos((q / c3_)) / 3.0);
+        float s = cos(h);
+        float t_1 = (sin(h) * sqrt(3.0));
+        float rx_1 = sqrt(((-(c_2) * ((s + t_1) + 2.0)) + m2_));
+        float ry_1 = sqrt(((-(c_2) * ((s - t_1) + 2.0)) + m2_));
+        co = ((((ry_1 + (sign(l) * rx_1)) + (abs(g) / (rx_1 * ry_1))) - m) / 2.0);
+    } else {
+        float h_1 = (((2.0 * m) * n) * sqrt(d_5));
+        float s_1 = (sign((q + h_1)) * pow(abs((q + h_1)), 0.33333334));
+        float t_2 = (sign((q - h_1)) * pow(abs((q - h_1)), 0.33333334));
+        float rx_2 = (((-(s_1) - t_2) - (c_2 * 4.0)) + (2.0 * m2_));
+        float ry_2 = ((s_1 - t_2) * sqrt(3.0));
+        float rm = sqrt(((rx_2 * rx_2) + (ry_2 * ry_2)));
+        float k = (ry_2 / sqrt((rm - rx_2)));
+        co = (((k + ((2.0 * g) / rm)) - m) / 2.0);
+    }
+    float _e144 = co;
+    float _e145 = co;
+    float si = sqrt((1.0 - (_e144 * _e145)));
+    float _e151 = ab.x;
+    float _e152 = co;
+    float _e155 = ab.y;
+    vec2 r = vec2((_e151 * _e152), (_e155 * si));
+    vec2 _e158 = p_1;
+    float _e162 = p_1.y;
+    return (length((r - _e158)) * sign((_e162 - r.y)));
+}
+
+float sd_round_rect(vec2 p_2, vec2 size_in, vec4 rx, vec4 ry) {
+    vec2 corner = vec2(0.0);
+    vec2 size_1 = (size_in * 0.5);
+    corner = vec2((-(size_1.x) + rx.x), (-(size_1.y) + ry.x));
+    float _e23 = corner.x;
+    float _e28 = corner.y;
+    if (((((rx.x * ry.x) > 0.0) && (p_2.x < _e23)) && (p_2.y <= _e28))) {
+        vec2 _e31 = corner;
+        float _e36 = sd_ellipse((p_2 - _e31), vec2(rx.x, ry.x));
+        return _e36;
+    }
+    corner = vec2((size_1.x - rx.y), (-(size_1.y) + ry.y));
+    float _e52 = corner.x;
+    float _e57 = corner.y;
+    if (((((rx.y * ry.y) > 0.0) && (p_2.x >= _e52)) && (p_2.y <= _e57))) {
+        vec2 _e60 = corner;
+        float _e65 = sd_ellipse((p_2 - _e60), vec2(rx.y, ry.y));
+        return _e65;
+    }
+    corner = vec2((size_1.x - rx.z), (size_1.y - ry.z));
+    float _e80 = corner.x;
+    float _e85 = corner.y;
+    if (((((rx.z * ry.z) > 0.0) && (p_2.x >= _e80)) && (p_2.y >= _e85))) {
+        vec2 _e88 = corner;
+        float _e93 = sd_ellipse((p_2 - _e88), vec2(rx.z, ry.z));
+        return _e93;
+    }
+    corner = vec2((-(size_1.x) + rx.w), (size_1.y - ry.w));
+    float _e109 = corner.x;
+    float _e114 = corner.y;
+    if (((((rx.w * ry.w) > 0.0) && (p_2.x < _e109)) && (p_2.y > _e114))) {
+        vec2 _e117 = corner;
+        float _e122 = sd_ellipse((p_2 - _e117), vec2(rx.w, ry.w));
+        return _e122;
+    }
+    float _e123 = sd_rect(p_2, size_1);
+    return _e123;
+}
+
+vec2 transform_affine(vec2 val, vec2 a, vec2 b, vec2 c) {
+    return (((val.x * a) + (val.y * b)) + c);
+}
+
+float antialias2_(float d) {
+    float _e3 = fwidth(d);
+    return smoothstep(-0.6180469, 0.6180469, (d / _e3));
+}
+
+vec4 apply_clip(vec2 obj_coord_1, vec4 color_1) {
+    vec4 out_color_1 = vec4(0.0);
+    uint i_1 = 0u;
+    vec2 p_3 = vec2(0.0);
+    float d_clip = 0.0;
+    out_color_1 = color_1;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e54 = i_1;
+            i_1 = (_e54 + 1u);
+        }
+        loop_init = false;
+        uint _e5 = i_1;
+        uint _e8 = _group_0_binding_0_fs.clip_size;
+        if ((_e5 < _e8)) {
+        } else {
+            break;
+        }
+        {
+            uint _e12 = i_1;
+            mat4x4 data = _group_0_binding_0_fs.clip[_e12];
+            vec2 origin = data[0].xy;
+            vec2 size_2 = data[0].zw;
+            vec4 radii_x = floor((data[1] / vec4(65536.0)));
+            vec4 radii_y = floor((data[1] - (radii_x * 65536.0)));
+            bool inverse_ = (data[3].z != 0.0);
+            vec2 _e39 = transform_affine(obj_coord_1, data[2].xy, data[2].zw, data[3].xy);
+            p_3 = _e39;
+            vec2 _e41 = p_3;
+            p_3 = (_e41 - origin);
+            vec2 _e43 = p_3;
+            float _e44 = sd_round_rect(_e43, size_2, radii_x, radii_y);
+            d_clip = _e44;
+            if (inverse_) {
+                float _e46 = d_clip;
+                d_clip = -(_e46);
+            }
+            float _e48 = d_clip;
+            float _e50 = antialias2_(-(_e48));
+            vec4 _e51 = out_color_1;
+            out_color_1 = (_e51 * _e50);
+        }
+    }
+    vec4 _e56 = out_color_1;
+    return _e56;
+}
+
+vec4 sample0_(vec2 uv) {
+    vec4 _e4 = textureLod(_group_1_binding_0_fs, vec2(uv), 0.0);
+    return _e4;
+}
+
+vec4 sample1_(vec2 uv_1) {
+    vec4 _e4 = textureLod(_group_1_binding_1_fs, vec2(uv_1), 0.0);
+    return _e4;
+}
+
+uint fill_type(VertexOutput input_1) {
+    return uint((input_1.data0_.x + 0.5));
+}
+
+vec4 tile_rect_uv() {
+    vec4 _e3 = _group_0_binding_0_fs.vector[0];
+    return _e3;
+}
+
+vec2 tile_size() {
+    vec4 _e3 = _group_0_binding_0_fs.vector[1];
+    return _e3.zw;
+}
+
+vec2 pattern_transform_a() {
+    vec4 _e3 = _group_0_binding_0_fs.vector[2];
+    return _e3.xy;
+}
+
+vec2 pattern_transform_b() {
+    vec4 _e3 = _group_0_binding_0_fs.vector[2];
+    return _e3.zw;
+}
+
+vec2 pattern_transform_c() {
+    vec4 _e3 = _group_0_binding_0_fs.vector[3];
+    return _e3.xy;
+}
+
+uint gradient_num_stops(VertexOutput input_2) {
+    return uint((input_2.data0_.y + 0.5));
+}
+
+bool gradient_is_radial(VertexOutput input_3) {
+    return (uint((input_3.data0_.z + 0.5)) != 0u);
+}
+
+vec2 gradient_p0_(VertexOutput input_4) {
+    return input_4.data1_.xy;
+}
+
+vec2 gradient_p1_(VertexOutput input_5) {
+    return input_5.data1_.zw;
+}
+
+GradientStop get_gradient_stop(VertexOutput input_6, uint offset) {
+    GradientStop result = GradientStop(0.0, vec4(0.0));
+    if ((offset < 4u)) {
+        result.percent = input_6.data2_[offset];
+        switch(offset) {
+            case 0u: {
+                result.color = input_6.data3_;
+                break;
+            }
+            case 1u: {
+                result.color = input_6.data4_;
+                break;
+            }
+            case 2u: {
+                result.color = input_6.data5_;
+                break;
+            }
+            default: {
+                result.color = input_6.data6_;
+                break;
+            }
+        }
+    } else {
+        float _e19 = scalar((offset - 4u));
+        result.percent = _e19;
+        vec4 _e26 = _group_0_binding_0_fs.vector[(offset - 4u)];
+        result.color = _e26;
+    }
+    GradientStop _e27 = result;
+    return _e27;
+}
+
+float antialias(float d_1, float width, float median) {
+    return smoothstep((median - width), (median + width), d_1);
+}
+
+vec4 fill_solid(VertexOutput input_7) {
+    return input_7.color;
+}
+
+vec4 fill_image(VertexOutput input_8) {
+    vec4 _e2 = sample0_(input_8.tex_coord);
+    return (_e2 * input_8.color);
+}
+
+vec4 fill_pattern_image(VertexOutput input_9) {
+    vec2 transformed = vec2(0.0);
+    vec2 uv_2 = vec2(0.0);
+    vec4 _e1 = tile_rect_uv();
+    vec2 _e3 = pattern_transform_a();
+    vec2 _e4 = pattern_transform_b();
+    vec2 _e5 = pattern_transform_c();
+    vec2 _e6 = transform_affine(input_9.obj_coord, _e3, _e4, _e5);
+    transformed = _e6;
+    vec2 _e8 = tile_size();
+    vec2 _e9 = transformed;
+    transformed = (_e9 / _e8);
+    vec2 _e11 = transformed;
+    uv_2 = fract(_e11);
+    vec2 _e17 = uv_2;
+    uv_2 = (_e17 * (_e1.zw - _e1.xy));
+    vec2 _e20 = uv_2;
+    uv_2 = (_e20 + _e1.xy);
+    vec2 _e22 = uv_2;
+    vec4 _e23 = sample0_(_e22);
+    return (_e23 * input_9.color);
+}
+
+float ramp(float in_min, float in_max, float val_1) {
+    return clamp(((val_1 - in_min) / (in_max - in_min)), 0.0, 1.0);
+}
+
+vec4 fill_pattern_gradient(VertexOutput input_10) {
+    float t = 0.0;
+    GradientStop previous = GradientStop(0.0, vec4(0.0));
+    vec4 out_color_2 = vec4(0.0);
+    uint i_2 = 2u;
+    uint _e1 = gradient_num_stops(input_10);
+    vec2 _e2 = gradient_p0_(input_10);
+    vec2 _e3 = gradient_p1_(input_10);
+    bool _e6 = gradient_is_radial(input_10);
+    if (_e6) {
+        float r0_ = _e3.x;
+        float r1_ = _e3.y;
+        float r_delta = (r1_ - r0_);
+        t = clamp(((distance(input_10.tex_coord, _e2) / r_delta) - (r0_ / r_delta)), 0.0, 1.0);
+    } else {
+        vec2 v = (_e3 - _e2);
+        t = clamp((dot((input_10.tex_coord - _e2), v) / dot(v, v)), 0.0, 1.0);
+    }
+    GradientStop _e24 = get_gradient_stop(input_10, 0u);
+    previous = _e24;
+    GradientStop _e27 = get_gradient_stop(input_10, 1u);
+    vec4 _e29 = previous.color;
+    float _e32 = previous.percent;
+    float _e34 = t;
+    float _e35 = ramp(_e32, _e27.percent, _e34);
+    out_color_2 = mix(_e29, _e27.color, _e35);
+    previous = _e27;
+    bool loop_init_1 = true;
+    while(true) {
+        if (!loop_init_1) {
+            uint _e55 = i_2;
+            i_2 = (_e55 + 1u);
+        }
+        loop_init_1 = false;
+        uint _e40 = i_2;
+        if ((_e40 < min(_e1, 7u))) {
+        } else {
+            break;
+        }
+        {
+            uint _e44 = i_2;
+            GradientStop _e45 = get_gradient_stop(input_10, _e44);
+            vec4 _e46 = out_color_2;
+            float _e49 = previous.percent;
+            float _e51 = t;
+            float _e52 = ramp(_e49, _e45.percent, _e51);
+            out_color_2 = mix(_e46, _e45.color, _e52);
+            previous = _e45;
+        }
+    }
+    vec4 _e57 = out_color_2;
+    return _e57;
+}
+
+vec4 blend(vec4 src, vec4 dest) {
+    return vec4((src.xyz + (dest.xyz * (1.0 - src.w))), (src.w + (dest.w * (1.0 - src.w))));
+}
+
+float inner_stroke(float stroke_width, float d_2) {
+    float _e5 = antialias(-(d_2), AA_WIDTH, 0.0);
+    float _e8 = antialias(-(d_2), AA_WIDTH, stroke_width);
+    return min(_e5, (1.0 - _e8));
+}
+
+vec4 fill_rounded_rect(VertexOutput input_11) {
+    float alpha = 0.0;
+    vec4 out_color_3 = vec4(0.0);
+    vec2 size_3 = input_11.data0_.zw;
+    vec2 p_4 = ((input_11.tex_coord - vec2(0.5)) * size_3);
+    float _e10 = sd_round_rect(p_4, size_3, input_11.data1_, input_11.data2_);
+    float _e14 = antialias(-(_e10), AA_WIDTH, 0.0);
+    alpha = (_e14 * input_11.color.w);
+    float _e21 = alpha;
+    float _e23 = alpha;
+    out_color_3 = vec4((input_11.color.xyz * _e21), _e23);
+    float stroke_width_1 = input_11.data3_.x;
+    vec4 stroke_color = input_11.data4_;
+    if ((stroke_width_1 > 0.0)) {
+        float _e31 = inner_stroke(stroke_width_1, _e10);
+        alpha = (_e31 * stroke_color.w);
+        float _e35 = alpha;
+        float _e37 = alpha;
+        vec4 stroke = vec4((stroke_color.xyz * _e35), _e37);
+        vec4 _e39 = out_color_3;
+        vec4 _e40 = blend(stroke, _e39);
+        out_color_3 = _e40;
+    }
+    vec4 _e41 = out_color_3;
+    return _e41;
+}
+
+bool box_shadow_inset(VertexOutput input_12) {
+    return (uint((input_12.data0_.y + 0.5)) != 0u);
+}
+
+vec2 box_shadow_distances(VertexOutput input_13) {
+    vec2 p_5 = input_13.obj_coord;
+    vec2 origin_1 = input_13.data1_.xy;
+    vec2 size_4 = input_13.data1_.zw;
+    vec2 clip_origin = input_13.data4_.xy;
+    vec2 clip_size = input_13.data4_.zw;
+    float _e13 = sd_round_rect((p_5 - clip_origin), clip_size, input_13.data5_, input_13.data6_);
+    float _e17 = sd_round_rect((p_5 - origin_1), size_4, input_13.data2_, input_13.data3_);
+    bool _e18 = box_shadow_inset(input_13);
+    if (_e18) {
+        return vec2(-(_e17), -(_e13));
+    }
+    return vec2(_e13, _e17);
+}
+
+vec4 fill_box_shadow(VertexOutput input_14, float d_3) {
+    float alpha_1 = 0.0;
+    float radius = input_14.data0_.z;
+    if ((radius >= 1.0)) {
+        float _e13 = antialias(-(d_3), ((radius * 2.0) + 0.2), 0.0);
+        alpha_1 = ((pow(_e13, 1.9) * 3.3) / pow((radius * 1.2), 0.15));
+    } else {
+        bool _e27 = box_shadow_inset(input_14);
+        float _e29 = antialias(-(d_3), AA_WIDTH, (_e27 ? -1.0 : 1.0));
+        alpha_1 = _e29;
+    }
+    float _e30 = alpha_1;
+    alpha_1 = (clamp(_e30, 0.0, 1.0) * input_14.color.w);
+    float _e39 = alpha_1;
+    float _e41 = alpha_1;
+    return vec4((input_14.color.xyz * _e39), _e41);
+}
+
+vec3 blend_overlay(vec3 src_1, vec3 dest_1) {
+    return mix((vec3(1.0) - ((2.0 * (vec3(1.0) - dest_1)) * (vec3(1.0) - src_1))), ((2.0 * dest_1) * src_1), lessThan(dest_1, vec3(0.5)));
+}
+
+vec3 blend_color_dodge(vec3 src_2, vec3 dest_2) {
+    return mix(min((dest_2 / (vec3(1.0) - src_2)), vec3(1.0)), src_2, equal(src_2, vec3(1.0)));
+}
+
+vec3 blend_color_burn(vec3 src_3, vec3 dest_3) {
+    return mix(max((vec3(1.0) - ((vec3(1.0) - dest_3) / src_3)), vec3(0.0)), src_3, equal(src_3, vec3(0.0)));
+}
+
+vec3 blend_soft_light(vec3 src_4, vec3 dest_4) {
+    return mix(((sqrt(dest_4) * ((2.0 * src_4) - vec3(1.0))) + ((2.0 * dest_4) * (vec3(1.0) - src_4))), (((2.0 * dest_4) * src_4) + ((dest_4 * dest_4) * (vec3(1.0) - (2.0 * src_4)))), lessThan(src_4, vec3(0.5)));
+}
+
+vec3 rgb2hsl(vec3 col) {
+    float minc = min(col.x, min(col.y, col.z));
+    float maxc = max(col.x, max(col.y, col.z));
+    vec3 mask = (step(col.yxx, col.xyz) * step(col.zzy, col.xyz));
+    vec3 h_2 = ((mask * (vec3(0.0, 2.0, 4.0) + ((col.yzx - col.zxy) / vec3(((maxc - minc) + 1e-7))))) / vec3(6.0));
+    return vec3(fract((((1.0 + h_2.x) + h_2.y) + h_2.z)), ((maxc - minc) / ((1.0 - abs(((minc + maxc) - 1.0))) + 1e-7)), ((minc + maxc) * 0.5));
+}
+
+vec3 hsl2rgb(vec3 c_1) {
+    vec3 rgb = clamp((abs((((vec3((c_1.x * 6.0)) + vec3(0.0, 4.0, 2.0)) - vec3(6.0) * trunc((vec3((c_1.x * 6.0)) + vec3(0.0, 4.0, 2.0)) / vec3(6.0))) - vec3(3.0))) - vec3(1.0)), vec3(0.0), vec3(1.0));
+    return (vec3(c_1.z) + ((c_1.y * (rgb - vec3(0.5))) * (1.0 - abs(((2.0 * c_1.z) - 1.0)))));
+}
+
+vec3 blend_hue(vec3 src_5, vec3 dest_5) {
+    vec3 _e2 = rgb2hsl(dest_5);
+    vec3 _e3 = rgb2hsl(src_5);
+    vec3 _e8 = hsl2rgb(vec3(_e3.x, _e2.y, _e2.z));
+    return _e8;
+}
+
+vec3 blend_saturation(vec3 src_6, vec3 dest_6) {
+    vec3 _e2 = rgb2hsl(dest_6);
+    vec3 _e4 = rgb2hsl(src_6);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e4.y, _e2.z));
+    return _e8;
+}
+
+vec3 blend_color(vec3 src_7, vec3 dest_7) {
+    vec3 _e2 = rgb2hsl(src_7);
+    vec3 _e5 = rgb2hsl(dest_7);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e2.y, _e5.z));
+    return _e8;
+}
+
+vec3 blend_luminosity(vec3 src_8, vec3 dest_8) {
+    vec3 _e2 = rgb2hsl(dest_8);
+    vec3 _e5 = rgb2hsl(src_8);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e2.y, _e5.z));
+    return _e8;
+}
+
+vec4 fill_blend(VertexOutput input_15) {
+    vec4 _e1 = fill_image(input_15);
+    vec4 _e3 = sample1_(input_15.obj_coord);
+    switch(uint((input_15.data0_.y + 0.5))) {
+        case 0u: {
+            return vec4(0.0);
+        }
+        case 1u: {
+            return _e1;
+        }
+        case 2u: {
+            return (_e1 + (_e3 * (1.0 - _e1.w)));
+        }
+        case 3u: {
+            return (_e1 * _e3.w);
+        }
+        case 4u: {
+            return (_e1 * (1.0 - _e3.w));
+        }
+        case 5u: {
+            return ((_e1 * _e3.w) + (_e3 * (1.0 - _e1.w)));
+        }
+        case 6u: {
+            return ((_e1 * (1.0 - _e3.w)) + _e3);
+        }
+        case 7u: {
+            return (_e3 * _e1.w);
+        }
+        case 8u: {
+            return (_e3 * (1.0 - _e1.w));
+        }
+        case 9u: {
+            return ((_e1 * (1.0 - _e3.w)) + (_e3 * _e1.w));
+        }
+        case 10u: {
+            return clamp(((_e1 * (1.0 - _e3.w)) + (_e3 * (1.0 - _e1.w))), vec4(0.0), vec4(1.0));
+        }
+        case 11u: {
+            return vec4((min(_e1.xyz, _e3.xyz) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 12u: {
+            return clamp((_e1 + _e3), vec4(0.0), vec4(1.0));
+        }
+        case 13u: {
+            return vec4((abs((_e3.xyz - _e1.xyz)) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 14u: {
+            return vec4(((_e1.xyz * _e3.xyz) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 15u: {
+            return vec4(((vec3(1.0) - ((vec3(1.0) - _e3.xyz) * (vec3(1.0) - _e1.xyz))) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 16u: {
+            vec3 _e107 = blend_overlay(_e1.xyz, _e3.xyz);
+            return vec4((_e107 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 17u: {
+            return vec4((max(_e1.xyz, _e3.xyz) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 18u: {
+            vec3 _e125 = blend_color_dodge(_e1.xyz, _e3.xyz);
+            return vec4((_e125 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 19u: {
+            vec3 _e134 = blend_color_burn(_e1.xyz, _e3.xyz);
+            return vec4((_e134 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 20u: {
+            vec3 _e143 = blend_overlay(_e3.xyz, _e1.xyz);
+            return vec4((_e143 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 21u: {
+            vec3 _e152 = blend_soft_light(_e1.xyz, _e3.xyz);
+            return vec4((_e152 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 22u: {
+            return vec4((((_e3.xyz + _e1.xyz) - ((2.0 * _e3.xyz) * _e1.xyz)) * _e1.w), (_e3.w * _e1.w));
+        }
+        case 23u: {
+            vec3 _e176 = blend_hue(_e1.xyz, _e3.xyz);
+            return vec4((_e176 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 24u: {
+            vec3 _e185 = blend_saturation(_e1.xyz, _e3.xyz);
+            return vec4((_e185 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 25u: {
+            vec3 _e194 = blend_color(_e1.xyz, _e3.xyz);
+            return vec4((_e194 * _e1.w), (_e3.w * _e1.w));
+        }
+        case 26u: {
+            vec3 _e203 = blend_luminosity(_e1.xyz, _e3.xyz);
+            return vec4((_e203 * _e1.w), (_e3.w * _e1.w));
+        }
+        default: {
+            return _e1;
+        }
+    }
+}
+
+vec4 fill_mask(VertexOutput input_16) {
+    vec4 _e1 = fill_image(input_16);
+    vec4 _e3 = sample1_(input_16.obj_coord);
+    float alpha_2 = _e3.w;
+    return (_e1 * alpha_2);
+}
+
+vec4 fill_glyph(VertexOutput input_17) {
+    vec4 _e2 = sample0_(input_17.tex_coord);
+    float alpha_3 = (_e2.w * input_17.color.w);
+    float fill_color_luma = input_17.data0_.y;
+    vec4 _e10 = sample1_(vec2(alpha_3, fill_color_luma));
+    float corrected_alpha = _e10.w;
+    return vec4((input_17.color.xyz * corrected_alpha), corrected_alpha);
+}
+
+void main() {
+    VertexOutput input_ = VertexOutput(gl_FragCoord, _vs2fs_location0, _vs2fs_location1, _vs2fs_location2, _vs2fs_location3, _vs2fs_location4, _vs2fs_location5, _vs2fs_location6, _vs2fs_location7, _vs2fs_location8, _vs2fs_location9);
+    vec4 out_color = vec4(0.0);
+    bool discarded = false;
+    out_color = input_.color;
+    uint _e5 = fill_type(input_);
+    switch(_e5) {
+        case 0u: {
+            vec4 _e6 = fill_solid(input_);
+            out_color = _e6;
+            break;
+        }
+        case 1u: {
+            vec4 _e7 = fill_image(input_);
+            out_color = _e7;
+            break;
+        }
+        case 2u: {
+            vec4 _e8 = fill_pattern_image(input_);
+            out_color = _e8;
+            break;
+        }
+        case 3u: {
+            vec4 _e9 = fill_pattern_gradient(input_);
+            out_color = _e9;
+            break;
+        }
+        case 7u: {
+            vec4 _e10 = fill_rounded_rect(input_);
+            out_color = _e10;
+            break;
+        }
+        case 8u: {
+            vec2 _e11 = box_shadow_distances(input_);
+            discarded = (_e11.x < 0.0);
+            vec4 _e16 = fill_box_shadow(input_, _e11.y);
+            out_color = _e16;
+            break;
+        }
+        case 9u: {
+            vec4 _e17 = fill_blend(input_);
+            out_color = _e17;
+            break;
+        }
+        case 10u: {
+            vec4 _e18 = fill_mask(input_);
+            out_color = _e18;
+            break;
+        }
+        case 11u: {
+            vec4 _e19 = fill_glyph(input_);
+            out_color = _e19;
+            break;
+        }
+        default: {
+            break;
+        }
+    }
+    vec4 _e21 = out_color;
+    vec4 _e22 = apply_clip(input_.obj_coord, _e21);
+    out_color = _e22;
+    bool _e23 = discarded;
+    if (_e23) {
+        discard;
+    }
+    vec4 _e24 = out_color;
+    _fs2p_location0 = _e24;
+    return;
+}
+
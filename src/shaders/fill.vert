@@ -0,0 +1,484 @@
+#version 450 core
+struct Uniforms {
+    vec4 state;
+    mat4x4 transform;
+    vec4 scalar4_[2];
+    vec4 vector[8];
+    uint clip_size;
+    mat4x4 clip[8];
+};
+struct VertexOutput {
+    vec4 position;
+    vec4 color;
+    vec2 tex_coord;
+    vec2 obj_coord;
+    vec4 data0_;
+    vec4 data1_;
+    vec4 data2_;
+    vec4 data3_;
+    vec4 data4_;
+    vec4 data5_;
+    vec4 data6_;
+};
+struct GradientStop {
+    float percent;
+    vec4 color;
+};
+const float AA_WIDTH = 0.354;
+
+layout(std140, binding = 0) uniform Uniforms_block_0Vertex { Uniforms _group_0_binding_0_vs; };
+
+layout(location = 0) in vec2 _p2vs_location0;
+layout(location = 1) in uvec4 _p2vs_location1;
+layout(location = 2) in vec2 _p2vs_location2;
+layout(location = 3) in vec2 _p2vs_location3;
+layout(location = 4) in vec4 _p2vs_location4;
+layout(location = 5) in vec4 _p2vs_location5;
+layout(location = 6) in vec4 _p2vs_location6;
+layout(location = 7) in vec4 _p2vs_location7;
+layout(location = 8) in vec4 _p2vs_location8;
+layout(location = 9) in vec4 _p2vs_location9;
+layout(location = 10) in vec4 _p2vs_location10;
+layout(location = 0) smooth out vec4 _vs2fs_location0;
+layout(location = 1) smooth out vec2 _vs2fs_location1;
+layout(location = 2) smooth out vec2 _vs2fs_location2;
+layout(location = 3) smooth out vec4 _vs2fs_location3;
+layout(location = 4) smooth out vec4 _vs2fs_location4;
+layout(location = 5) smooth out vec4 _vs2fs_location5;
+layout(location = 6) smooth out vec4 _vs2fs_location6;
+layout(location = 7) smooth out vec4 _vs2fs_location7;
+layout(location = 8) smooth out vec4 _vs2fs_location8;
+layout(location = 9) smooth out vec4 _vs2fs_location9;
+
+float scalar(uint i) {
+    float _e9 = _group_0_binding_0_vs.scalar4_[(i / 4u)][(i % 4u)];
+    return _e9;
+}
+
+float sd_rect(vec2 p, vec2 size) {
+    vec2 d_4 = (abs(p) - size);
+    return (min(max(d_4.x, d_4.y), 0.0) + length(max(d_4, vec2(0.0))));
+}
+
+float sd_ellipse(vec2 p_in, vec2 ab_in) {
+    vec2 p_1 = vec2(0.0);
+    vec2 ab = vec2(0.0);
+    float co = 0.0;
+    if ((abs((ab_in.x - ab_in.y)) < 0.1)) {
+        return (length(p_in) - ab_in.x);
+    }
+    p_1 = abs(p_in);
+    ab = ab_in;
+    float _e15 = p_1.x;
+    float _e17 = p_1.y;
+    if ((_e15 > _e17)) {
+        vec2 _e19 = p_1;
+        p_1 = _e19.yx;
+        vec2 _e21 = ab;
+        ab = _e21.yx;
+    }
+    float _e24 = ab.y;
+    float _e26 = ab.y;
+    float _e29 = ab.x;
+    float _e31 = ab.x;
+    float l = ((_e24 * _e26) - (_e29 * _e31));
+    float _e35 = ab.x;
+    float _e37 = p_1.x;
+    float m = ((_e35 * _e37) / l);
+    float _e41 = ab.y;
+    float _e43 = p_1.y;
+    float n = ((_e41 * _e43) / l);
+    float m2_ = (m * m);
+    float n2_ = (n * n);
+    float c_2 = (((m2_ + n2_) - 1.0) / 3.0);
+    float c3_ = ((c_2 * c_2) * c_2);
+    float q = (c3_ + ((m2_ * n2_) * 2.0));
+    float d_5 = (c3_ + (m2_ * n2_));
+    float g = (m + (m * n2_));
+    if ((d_5 < 0.0)) {
+        float h = (acos((q / c3_)) / 3.0);
+        float s = cos(h);
+        float t_1 = (sin(h) * sqrt(3.0));
+        float rx_1 = sqrt(((-(c_2) * ((s + t_1) + 2.0)) + m2_));
+        float ry_1 = sqrt(((-(c_2) * ((s - t_1) + 2.0)) + m2_));
+        co = ((((ry_1 + (sign(l) * rx_1)) + (abs(g) / (rx_1 * ry_1))) - m) / 2.0);
+    } else {
+        float h_1 = (((2.0 * m) * n) * sqrt(d_5));
+        float s_1 = (sign((q + h_1)) * pow(abs((q + h_1)), 0.33333334));
+        float t_2 = (sign((q - h_1)) * pow(abs((q - h_1)), 0.33333334));
+        float rx_2 = (((-(s_1) - t_2) - (c_2 * 4.0)) + (2.0 * m2_));
+        float ry_2 = ((s_1 - t_2) * sqrt(3.0));
+        float rm = sqrt(((rx_2 * rx_2) + (ry_2 * ry_2)));
+        float k = (ry_2 / sqrt((rm - rx_2)));
+        co = (((k + ((2.0 * g) / rm)) - m) / 2.0);
+    }
+    float _e144 = co;
+    float _e145 = co;
+    float si = sqrt((1.0 - (_e144 * _e145)));
+    float _e151 = ab.x;
+    float _e152 = co;
+    float _e155 = ab.y;
+    vec2 r = vec2((_e151 * _e152), (_e155 * si));
+    vec2 _e158 = p_1;
+    float _e162 = p_1.y;
+    return (length((r - _e158)) * sign((_e162 - r.y)));
+}
+
+float sd_round_rect(vec2 p_2, vec2 size_in, vec4 rx, vec4 ry) {
+    vec2 corner = vec2(0.0);
+    vec2 size_1 = (size_in * 0.5);
+    corner = vec2((-(size_1.x) + rx.x), (-(size_1.y) + ry.x));
+    float _e23 = corner.x;
+    float _e28 = corner.y;
+    if (((((rx.x * ry.x) > 0.0) && (p_2.x < _e23)) && (p_2.y <= _e28))) {
+        vec2 _e31 = corner;
+        float _e36 = sd_ellipse((p_2 - _e31), vec2(rx.x, ry.x));
+        return _e36;
+    }
+    corner = vec2((size_1.x - rx.y), (-(size_1.y) + ry.y));
+    float _e52 = corner.x;
+    float _e57 = corner.y;
+    if (((((rx.y * ry.y) > 0.0) && (p_2.x >= _e52)) && (p_2.y <= _e57))) {
+        vec2 _e60 = corner;
+        float _e65 = sd_ellipse((p_2 - _e60), vec2(rx.y, ry.y));
+        return _e65;
+    }
+    corner = vec2((size_1.x - rx.z), (size_1.y - ry.z));
+    float _e80 = corner.x;
+    float _e85 = corner.y;
+    if (((((rx.z * ry.z) > 0.0) && (p_2.x >= _e80)) && (p_2.y >= _e85))) {
+        vec2 _e88 = corner;
+        float _e93 = sd_ellipse((p_2 - _e88), vec2(rx.z, ry.z));
+        return _e93;
+    }
+    corner = vec2((-(size_1.x) + rx.w), (size_1.y - ry.w));
+    float _e109 = corner.x;
+    float _e114 = corner.y;
+    if (((((rx.w * ry.w) > 0.0) && (p_2.x < _e109)) && (p_2.y > _e114))) {
+        vec2 _e117 = corner;
+        float _e122 = sd_ellipse((p_2 - _e117), vec2(rx.w, ry.w));
+        return _e122;
+    }
+    float _e123 = sd_rect(p_2, size_1);
+    return _e123;
+}
+
+vec2 transform_affine(vec2 val, vec2 a, vec2 b, vec2 c) {
+    return (((val.x * a) + (val.y * b)) + c);
+}
+
+uint fill_type(VertexOutput input_1) {
+    return uint((input_1.data0_.x + 0.5));
+}
+
+vec4 tile_rect_uv() {
+    vec4 _e3 = _group_0_binding_0_vs.vector[0];
+    return _e3;
+}
+
+vec2 tile_size() {
+    vec4 _e3 = _group_0_binding_0_vs.vector[1];
+    return _e3.zw;
+}
+
+vec2 pattern_transform_a() {
+    vec4 _e3 = _group_0_binding_0_vs.vector[2];
+    return _e3.xy;
+}
+
+vec2 pattern_transform_b() {
+    vec4 _e3 = _group_0_binding_0_vs.vector[2];
+    return _e3.zw;
+}
+
+vec2 pattern_transform_c() {
+    vec4 _e3 = _group_0_binding_0_vs.vector[3];
+    return _e3.xy;
+}
+
+uint gradient_num_stops(VertexOutput input_2) {
+    return uint((input_2.data0_.y + 0.5));
+}
+
+bool gradient_is_radial(VertexOutput input_3) {
+    return (uint((input_3.data0_.z + 0.5)) != 0u);
+}
+
+vec2 gradient_p0_(VertexOutput input_4) {
+    return input_4.data1_.xy;
+}
+
+vec2 gradient_p1_(VertexOutput input_5) {
+    return input_5.data1_.zw;
+}
+
+GradientStop get_gradient_stop(VertexOutput input_6, uint offset) {
+    GradientStop result = GradientStop(0.0, vec4(0.0));
+    if ((offset < 4u)) {
+        result.percent = input_6.data2_[offset];
+        switch(offset) {
+            case 0u: {
+                result.color = input_6.data3_;
+                break;
+            }
+            case 1u: {
+                result.color = input_6.data4_;
+                break;
+            }
+            case 2u: {
+                result.color = input_6.data5_;
+                break;
+            }
+            default: {
+                result.color = input_6.data6_;
+                break;
+            }
+        }
+    } else {
+        float _e19 = scalar((offset - 4u));
+        result.percent = _e19;
+        vec4 _e26 = _group_0_binding_0_vs.vector[(offset - 4u)];
+        result.color = _e26;
+    }
+    GradientStop _e27 = result;
+    return _e27;
+}
+
+float antialias(float d_1, float width, float median) {
+    return smoothstep((median - width), (median + width), d_1);
+}
+
+vec4 fill_solid(VertexOutput input_7) {
+    return input_7.color;
+}
+
+float ramp(float in_min, float in_max, float val_1) {
+    return clamp(((val_1 - in_min) / (in_max - in_min)), 0.0, 1.0);
+}
+
+vec4 fill_pattern_gradient(VertexOutput input_10) {
+    float t = 0.0;
+    GradientStop previous = GradientStop(0.0, vec4(0.0));
+    vec4 out_color_2 = vec4(0.0);
+    uint i_2 = 2u;
+    uint _e1 = gradient_num_stops(input_10);
+    vec2 _e2 = gradient_p0_(input_10);
+    vec2 _e3 = gradient_p1_(input_10);
+    bool _e6 = gradient_is_radial(input_10);
+    if (_e6) {
+        float r0_ = _e3.x;
+        float r1_ = _e3.y;
+        float r_delta = (r1_ - r0_);
+        t = clamp(((distance(input_10.tex_coord, _e2) / r_delta) - (r0_ / r_delta)), 0.0, 1.0);
+    } else {
+        vec2 v = (_e3 - _e2);
+        t = clamp((dot((input_10.tex_coord - _e2), v) / dot(v, v)), 0.0, 1.0);
+    }
+    GradientStop _e24 = get_gradient_stop(input_10, 0u);
+    previous = _e24;
+    GradientStop _e27 = get_gradient_stop(input_10, 1u);
+    vec4 _e29 = previous.color;
+    float _e32 = previous.percent;
+    float _e34 = t;
+    float _e35 = ramp(_e32, _e27.percent, _e34);
+    out_color_2 = mix(_e29, _e27.color, _e35);
+    previous = _e27;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e55 = i_2;
+            i_2 = (_e55 + 1u);
+        }
+        loop_init = false;
+        uint _e40 = i_2;
+        if ((_e40 < min(_e1, 7u))) {
+        } else {
+            break;
+        }
+        {
+            uint _e44 = i_2;
+            GradientStop _e45 = get_gradient_stop(input_10, _e44);
+            vec4 _e46 = out_color_2;
+            float _e49 = previous.percent;
+            float _e51 = t;
+            float _e52 = ramp(_e49, _e45.percent, _e51);
+            out_color_2 = mix(_e46, _e45.color, _e52);
+            previous = _e45;
+        }
+    }
+    vec4 _e57 = out_color_2;
+    return _e57;
+}
+
+vec4 blend(vec4 src, vec4 dest) {
+    return vec4((src.xyz + (dest.xyz * (1.0 - src.w))), (src.w + (dest.w * (1.0 - src.w))));
+}
+
+float inner_stroke(float stroke_width, float d_2) {
+    float _e5 = antialias(-(d_2), AA_WIDTH, 0.0);
+    float _e8 = antialias(-(d_2), AA_WIDTH, stroke_width);
+    return min(_e5, (1.0 - _e8));
+}
+
+vec4 fill_rounded_rect(VertexOutput input_11) {
+    float alpha = 0.0;
+    vec4 out_color_3 = vec4(0.0);
+    vec2 size_2 = input_11.data0_.zw;
+    vec2 p_4 = ((input_11.tex_coord - vec2(0.5)) * size_2);
+    float _e10 = sd_round_rect(p_4, size_2, input_11.data1_, input_11.data2_);
+    float _e14 = antialias(-(_e10), AA_WIDTH, 0.0);
+    alpha = (_e14 * input_11.color.w);
+    float _e21 = alpha;
+    float _e23 = alpha;
+    out_color_3 = vec4((input_11.color.xyz * _e21), _e23);
+    float stroke_width_1 = input_11.data3_.x;
+    vec4 stroke_color = input_11.data4_;
+    if ((stroke_width_1 > 0.0)) {
+        float _e31 = inner_stroke(stroke_width_1, _e10);
+        alpha = (_e31 * stroke_color.w);
+        float _e35 = alpha;
+        float _e37 = alpha;
+        vec4 stroke = vec4((stroke_color.xyz * _e35), _e37);
+        vec4 _e39 = out_color_3;
+        vec4 _e40 = blend(stroke, _e39);
+        out_color_3 = _e40;
+    }
+    vec4 _e41 = out_color_3;
+    return _e41;
+}
+
+bool box_shadow_inset(VertexOutput input_12) {
+    return (uint((input_12.data0_.y + 0.5)) != 0u);
+}
+
+vec2 box_shadow_distances(VertexOutput input_13) {
+    vec2 p_5 = input_13.obj_coord;
+    vec2 origin = input_13.data1_.xy;
+    vec2 size_3 = input_13.data1_.zw;
+    vec2 clip_origin = input_13.data4_.xy;
+    vec2 clip_size = input_13.data4_.zw;
+    float _e13 = sd_round_rect((p_5 - clip_origin), clip_size, input_13.data5_, input_13.data6_);
+    float _e17 = sd_round_rect((p_5 - origin), size_3, input_13.data2_, input_13.data3_);
+    bool _e18 = box_shadow_inset(input_13);
+    if (_e18) {
+        return vec2(-(_e17), -(_e13));
+    }
+    return vec2(_e13, _e17);
+}
+
+vec4 fill_box_shadow(VertexOutput input_14, float d_3) {
+    float alpha_1 = 0.0;
+    float radius = input_14.data0_.z;
+    if ((radius >= 1.0)) {
+        float _e13 = antialias(-(d_3), ((radius * 2.0) + 0.2), 0.0);
+        alpha_1 = ((pow(_e13, 1.9) * 3.3) / pow((radius * 1.2), 0.15));
+    } else {
+        bool _e27 = box_shadow_inset(input_14);
+        float _e29 = antialias(-(d_3), AA_WIDTH, (_e27 ? -1.0 : 1.0));
+        alpha_1 = _e29;
+    }
+    float _e30 = alpha_1;
+    alpha_1 = (clamp(_e30, 0.0, 1.0) * input_14.color.w);
+    float _e39 = alpha_1;
+    float _e41 = alpha_1;
+    return vec4((input_14.color.xyz * _e39), _e41);
+}
+
+vec3 blend_overlay(vec3 src_1, vec3 dest_1) {
+    return mix((vec3(1.0) - ((2.0 * (vec3(1.0) - dest_1)) * (vec3(1.0) - src_1))), ((2.0 * dest_1) * src_1), lessThan(dest_1, vec3(0.5)));
+}
+
+vec3 blend_color_dodge(vec3 src_2, vec3 dest_2) {
+    return mix(min((dest_2 / (vec3(1.0) - src_2)), vec3(1.0)), src_2, equal(src_2, vec3(1.0)));
+}
+
+vec3 blend_color_burn(vec3 src_3, vec3 dest_3) {
+    return mix(max((vec3(1.0) - ((vec3(1.0) - dest_3) / src_3)), vec3(0.0)), src_3, equal(src_3, vec3(0.0)));
+}
+
+vec3 blend_soft_light(vec3 src_4, vec3 dest_4) {
+    return mix(((sqrt(dest_4) * ((2.0 * src_4) - vec3(1.0))) + ((2.0 * dest_4) * (vec3(1.0) - src_4))), (((2.0 * dest_4) * src_4) + ((dest_4 * dest_4) * (vec3(1.0) - (2.0 * src_4)))), lessThan(src_4, vec3(0.5)));
+}
+
+vec3 rgb2hsl(vec3 col) {
+    float minc = min(col.x, min(col.y, col.z));
+    float maxc = max(col.x, max(col.y, col.z));
+    vec3 mask = (step(col.yxx, col.xyz) * step(col.zzy, col.xyz));
+    vec3 h_2 = ((mask * (vec3(0.0, 2.0, 4.0) + ((col.yzx - col.zxy) / vec3(((maxc - minc) + 1e-7))))) / vec3(6.0));
+    return vec3(fract((((1.0 + h_2.x) + h_2.y) + h_2.z)), ((maxc - minc) / ((1.0 - abs(((minc + maxc) - 1.0))) + 1e-7)), ((minc + maxc) * 0.5));
+}
+
+vec3 hsl2rgb(vec3 c_1) {
+    vec3 rgb = clamp((abs((((vec3((c_1.x * 6.0)) + vec3(0.0, 4.0, 2.0)) - vec3(6.0) * trunc((vec3((c_1.x * 6.0)) + vec3(0.0, 4.0, 2.0)) / vec3(6.0))) - vec3(3.0))) - vec3(1.0)), vec3(0.0), vec3(1.0));
+    return (vec3(c_1.z) + ((c_1.y * (rgb - vec3(0.5))) * (1.0 - abs(((2.0 * c_1.z) - 1.0)))));
+}
+
+vec3 blend_hue(vec3 src_5, vec3 dest_5) {
+    vec3 _e2 = rgb2hsl(dest_5);
+    vec3 _e3 = rgb2hsl(src_5);
+    vec3 _e8 = hsl2rgb(vec3(_e3.x, _e2.y, _e2.z));
+    return _e8;
+}
+
+vec3 blend_saturation(vec3 src_6, vec3 dest_6) {
+    vec3 _e2 = rgb2hsl(dest_6);
+    vec3 _e4 = rgb2hsl(src_6);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e4.y, _e2.z));
+    return _e8;
+}
+
+vec3 blend_color(vec3 src_7, vec3 dest_7) {
+    vec3 _e2 = rgb2hsl(src_7);
+    vec3 _e5 = rgb2hsl(dest_7);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e2.y, _e5.z));
+    return _e8;
+}
+
+vec3 blend_luminosity(vec3 src_8, vec3 dest_8) {
+    vec3 _e2 = rgb2hsl(dest_8);
+    vec3 _e5 = rgb2hsl(src_8);
+    vec3 _e8 = hsl2rgb(vec3(_e2.x, _e2.y, _e5.z));
+    return _e8;
+}
+
+void main() {
+    vec2 position = _p2vs_location0;
+    uvec4 color = _p2vs_location1;
+    vec2 tex_coord = _p2vs_location2;
+    vec2 obj_coord = _p2vs_location3;
+    vec4 data0_ = _p2vs_location4;
+    vec4 data1_ = _p2vs_location5;
+    vec4 data2_ = _p2vs_location6;
+    vec4 data3_ = _p2vs_location7;
+    vec4 data4_ = _p2vs_location8;
+    vec4 data5_ = _p2vs_location9;
+    vec4 data6_ = _p2vs_location10;
+    VertexOutput output_ = VertexOutput(vec4(0.0), vec4(0.0), vec2(0.0), vec2(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0), vec4(0.0));
+    mat4x4 _e15 = _group_0_binding_0_vs.transform;
+    output_.position = (_e15 * vec4(position, 0.0, 1.0));
+    output_.color = (vec4(color) / vec4(255.0));
+    output_.tex_coord = tex_coord;
+    output_.obj_coord = obj_coord;
+    output_.data0_ = data0_;
+    output_.data1_ = data1_;
+    output_.data2_ = data2_;
+    output_.data3_ = data3_;
+    output_.data4_ = data4_;
+    output_.data5_ = data5_;
+    output_.data6_ = data6_;
+    VertexOutput _e34 = output_;
+    gl_Position = _e34.position;
+    _vs2fs_location0 = _e34.color;
+    _vs2fs_location1 = _e34.tex_coord;
+    _vs2fs_location2 = _e34.obj_coord;
+    _vs2fs_location3 = _e34.data0_;
+    _vs2fs_location4 = _e34.data1_;
+    _vs2fs_location5 = _e34.data2_;
+    _vs2fs_location6 = _e34.data3_;
+    _vs2fs_location7 = _e34.data4_;
+    _vs2fs_location8 = _e34.data5_;
+    _vs2fs_location9 = _e34.data6_;
+    gl_Position.yz = vec2(-gl_Position.y, gl_Position.z * 2.0 - gl_Position.w);
+    return;
+}
+
@@ -0,0 +1,355 @@
+// Port of `vert_fill.hlsl` and `frag_fill.hlsl`, appended to `common.wgsl`.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) tex_coord: vec2<f32>,
+    @location(2) obj_coord: vec2<f32>,
+    @location(3) data0: vec4<f32>,
+    @location(4) data1: vec4<f32>,
+    @location(5) data2: vec4<f32>,
+    @location(6) data3: vec4<f32>,
+    @location(7) data4: vec4<f32>,
+    @location(8) data5: vec4<f32>,
+    @location(9) data6: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<u32>,
+    @location(2) tex_coord: vec2<f32>,
+    @location(3) obj_coord: vec2<f32>,
+    @location(4) data0: vec4<f32>,
+    @location(5) data1: vec4<f32>,
+    @location(6) data2: vec4<f32>,
+    @location(7) data3: vec4<f32>,
+    @location(8) data4: vec4<f32>,
+    @location(9) data5: vec4<f32>,
+    @location(10) data6: vec4<f32>,
+) -> VertexOutput {
+    var output: VertexOutput;
+    output.position = u.transform * vec4<f32>(position, 0.0, 1.0);
+    output.color = vec4<f32>(color) / 255.0;
+    output.tex_coord = tex_coord;
+    output.obj_coord = obj_coord;
+    output.data0 = data0;
+    output.data1 = data1;
+    output.data2 = data2;
+    output.data3 = data3;
+    output.data4 = data4;
+    output.data5 = data5;
+    output.data6 = data6;
+    return output;
+}
+
+const AA_WIDTH: f32 = 0.354;
+
+// Textures have a single mip, sampling the base level keeps this valid in non-uniform control flow.
+fn sample0(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(texture0, sampler0, uv, 0.0);
+}
+
+fn sample1(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(texture1, sampler0, uv, 0.0);
+}
+
+fn fill_type(input: VertexOutput) -> u32 { return u32(input.data0.x + 0.5); }
+fn tile_rect_uv() -> vec4<f32> { return u.vector[0]; }
+fn tile_size() -> vec2<f32> { return u.vector[1].zw; }
+fn pattern_transform_a() -> vec2<f32> { return u.vector[2].xy; }
+fn pattern_transform_b() -> vec2<f32> { return u.vector[2].zw; }
+fn pattern_transform_c() -> vec2<f32> { return u.vector[3].xy; }
+fn gradient_num_stops(input: VertexOutput) -> u32 { return u32(input.data0.y + 0.5); }
+fn gradient_is_radial(input: VertexOutput) -> bool { return u32(input.data0.z + 0.5) != 0u; }
+fn gradient_p0(input: VertexOutput) -> vec2<f32> { return input.data1.xy; }
+fn gradient_p1(input: VertexOutput) -> vec2<f32> { return input.data1.zw; }
+
+struct GradientStop {
+    percent: f32,
+    color: vec4<f32>,
+}
+
+fn get_gradient_stop(input: VertexOutput, offset: u32) -> GradientStop {
+    var result: GradientStop;
+    if (offset < 4u) {
+        result.percent = input.data2[offset];
+        switch offset {
+            case 0u: { result.color = input.data3; }
+            case 1u: { result.color = input.data4; }
+            case 2u: { result.color = input.data5; }
+            default: { result.color = input.data6; }
+        }
+    } else {
+        result.percent = scalar(offset - 4u);
+        result.color = u.vector[offset - 4u];
+    }
+    return result;
+}
+
+fn antialias(d: f32, width: f32, median: f32) -> f32 {
+    return smoothstep(median - width, median + width, d);
+}
+
+fn fill_solid(input: VertexOutput) -> vec4<f32> {
+    return input.color;
+}
+
+fn fill_image(input: VertexOutput) -> vec4<f32> {
+    return sample0(input.tex_coord) * input.color;
+}
+
+fn fill_pattern_image(input: VertexOutput) -> vec4<f32> {
+    let rect = tile_rect_uv();
+
+    // Apply the affine matrix and convert back to uv coordinate space.
+    var transformed = transform_affine(input.obj_coord,
+        pattern_transform_a(), pattern_transform_b(), pattern_transform_c());
+    transformed /= tile_size();
+
+    // Wrap UVs to [0.0, 1.0] so texture repeats properly, then clip to the tile rect.
+    var uv = fract(transformed);
+    uv *= rect.zw - rect.xy;
+    uv += rect.xy;
+
+    return sample0(uv) * input.color;
+}
+
+fn ramp(in_min: f32, in_max: f32, val: f32) -> f32 {
+    return clamp((val - in_min) / (in_max - in_min), 0.0, 1.0);
+}
+
+fn fill_pattern_gradient(input: VertexOutput) -> vec4<f32> {
+    let num_stops = gradient_num_stops(input);
+    let p0 = gradient_p0(input);
+    let p1 = gradient_p1(input);
+
+    var t = 0.0;
+    if (gradient_is_radial(input)) {
+        let r0 = p1.x;
+        let r1 = p1.y;
+        let r_delta = r1 - r0;
+        t = saturate(distance(input.tex_coord, p0) / r_delta - r0 / r_delta);
+    } else {
+        let v = p1 - p0;
+        t = saturate(dot(input.tex_coord - p0, v) / dot(v, v));
+    }
+
+    var previous = get_gradient_stop(input, 0u);
+    let first = get_gradient_stop(input, 1u);
+    var out_color = mix(previous.color, first.color, ramp(previous.percent, first.percent, t));
+    previous = first;
+    for (var i = 2u; i < min(num_stops, 7u); i++) {
+        let stop = get_gradient_stop(input, i);
+        out_color = mix(out_color, stop.color, ramp(previous.percent, stop.percent, t));
+        previous = stop;
+    }
+    return out_color;
+}
+
+fn blend(src: vec4<f32>, dest: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(src.rgb + dest.rgb * (1.0 - src.a), src.a + dest.a * (1.0 - src.a));
+}
+
+fn inner_stroke(stroke_width: f32, d: f32) -> f32 {
+    return min(antialias(-d, AA_WIDTH, 0.0), 1.0 - antialias(-d, AA_WIDTH, stroke_width));
+}
+
+fn fill_rounded_rect(input: VertexOutput) -> vec4<f32> {
+    let size = input.data0.zw;
+    let p = (input.tex_coord - 0.5) * size;
+    let d = sd_round_rect(p, size, input.data1, input.data2);
+
+    // Fill background
+    var alpha = antialias(-d, AA_WIDTH, 0.0) * input.color.a;
+    var out_color = vec4<f32>(input.color.rgb * alpha, alpha);
+
+    // Draw stroke
+    let stroke_width = input.data3.x;
+    let stroke_color = input.data4;
+
+    if (stroke_width > 0.0) {
+        alpha = inner_stroke(stroke_width, d) * stroke_color.a;
+        let stroke = vec4<f32>(stroke_color.rgb * alpha, alpha);
+        out_color = blend(stroke, out_color);
+    }
+
+    return out_color;
+}
+
+fn box_shadow_inset(input: VertexOutput) -> bool {
+    return u32(input.data0.y + 0.5) != 0u;
+}
+
+// Returns the clip distance, the pixel is discarded when it is negative, and the shadow distance.
+fn box_shadow_distances(input: VertexOutput) -> vec2<f32> {
+    let p = input.obj_coord;
+    let origin = input.data1.xy;
+    let size = input.data1.zw;
+    let clip_origin = input.data4.xy;
+    let clip_size = input.data4.zw;
+
+    let sd_clip = sd_round_rect(p - clip_origin, clip_size, input.data5, input.data6);
+    let sd_shadow = sd_round_rect(p - origin, size, input.data2, input.data3);
+
+    if (box_shadow_inset(input)) {
+        return vec2<f32>(-sd_shadow, -sd_clip);
+    }
+    return vec2<f32>(sd_clip, sd_shadow);
+}
+
+fn fill_box_shadow(input: VertexOutput, d: f32) -> vec4<f32> {
+    let radius = input.data0.z;
+
+    var alpha: f32;
+    if (radius >= 1.0) {
+        alpha = pow(antialias(-d, radius * 2.0 + 0.2, 0.0), 1.9) * 3.3 / pow(radius * 1.2, 0.15);
+    } else {
+        alpha = antialias(-d, AA_WIDTH, select(1.0, -1.0, box_shadow_inset(input)));
+    }
+
+    alpha = clamp(alpha, 0.0, 1.0) * input.color.a;
+    return vec4<f32>(input.color.rgb * alpha, alpha);
+}
+
+fn blend_overlay(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    return select(
+        1.0 - 2.0 * (1.0 - dest) * (1.0 - src),
+        2.0 * dest * src,
+        dest < vec3<f32>(0.5),
+    );
+}
+
+fn blend_color_dodge(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    return select(min(dest / (1.0 - src), vec3<f32>(1.0)), src, src == vec3<f32>(1.0));
+}
+
+fn blend_color_burn(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    return select(max(1.0 - (1.0 - dest) / src, vec3<f32>(0.0)), src, src == vec3<f32>(0.0));
+}
+
+fn blend_soft_light(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    return select(
+        sqrt(dest) * (2.0 * src - 1.0) + 2.0 * dest * (1.0 - src),
+        2.0 * dest * src + dest * dest * (1.0 - 2.0 * src),
+        src < vec3<f32>(0.5),
+    );
+}
+
+fn rgb2hsl(col: vec3<f32>) -> vec3<f32> {
+    let eps = 0.0000001;
+    let minc = min(col.r, min(col.g, col.b));
+    let maxc = max(col.r, max(col.g, col.b));
+    let mask = step(col.grr, col.rgb) * step(col.bbg, col.rgb);
+    let h = mask * (vec3<f32>(0.0, 2.0, 4.0) + (col.gbr - col.brg) / (maxc - minc + eps)) / 6.0;
+    return vec3<f32>(
+        fract(1.0 + h.x + h.y + h.z),                      // H
+        (maxc - minc) / (1.0 - abs(minc + maxc - 1.0) + eps), // S
+        (minc + maxc) * 0.5,                                  // L
+    );
+}
+
+fn hsl2rgb(c: vec3<f32>) -> vec3<f32> {
+    let rgb = clamp(abs((c.x * 6.0 + vec3<f32>(0.0, 4.0, 2.0)) % 6.0 - 3.0) - 1.0, vec3<f32>(0.0), vec3<f32>(1.0));
+    return c.z + c.y * (rgb - 0.5) * (1.0 - abs(2.0 * c.z - 1.0));
+}
+
+fn blend_hue(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    let base = rgb2hsl(dest);
+    return hsl2rgb(vec3<f32>(rgb2hsl(src).r, base.g, base.b));
+}
+
+fn blend_saturation(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    let base = rgb2hsl(dest);
+    return hsl2rgb(vec3<f32>(base.r, rgb2hsl(src).g, base.b));
+}
+
+fn blend_color(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    let blend_hsl = rgb2hsl(src);
+    return hsl2rgb(vec3<f32>(blend_hsl.r, blend_hsl.g, rgb2hsl(dest).b));
+}
+
+fn blend_luminosity(src: vec3<f32>, dest: vec3<f32>) -> vec3<f32> {
+    let base = rgb2hsl(dest);
+    return hsl2rgb(vec3<f32>(base.r, base.g, rgb2hsl(src).b));
+}
+
+fn fill_blend(input: VertexOutput) -> vec4<f32> {
+    let src = fill_image(input);
+    let dest = sample1(input.obj_coord);
+
+    switch u32(input.data0.y + 0.5) {
+        case 0u: { return vec4<f32>(0.0); } // Clear
+        case 1u: { return src; } // Source
+        case 2u: { return src + dest * (1.0 - src.a); } // Over
+        case 3u: { return src * dest.a; } // In
+        case 4u: { return src * (1.0 - dest.a); } // Out
+        case 5u: { return src * dest.a + dest * (1.0 - src.a); } // Atop
+        case 6u: { return src * (1.0 - dest.a) + dest; } // DestOver
+        case 7u: { return dest * src.a; } // DestIn
+        case 8u: { return dest * (1.0 - src.a); } // DestOut
+        case 9u: { return src * (1.0 - dest.a) + dest * src.a; } // DestAtop
+        case 10u: { return saturate(src * (1.0 - dest.a) + dest * (1.0 - src.a)); } // XOR
+        case 11u: { return vec4<f32>(min(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Darken
+        case 12u: { return saturate(src + dest); } // Add
+        case 13u: { return vec4<f32>(abs(dest.rgb - src.rgb) * src.a, dest.a * src.a); } // Difference
+        case 14u: { return vec4<f32>(src.rgb * dest.rgb * src.a, dest.a * src.a); } // Multiply
+        case 15u: { return vec4<f32>((1.0 - ((1.0 - dest.rgb) * (1.0 - src.rgb))) * src.a, dest.a * src.a); } // Screen
+        case 16u: { return vec4<f32>(blend_overlay(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Overlay
+        case 17u: { return vec4<f32>(max(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Lighten
+        case 18u: { return vec4<f32>(blend_color_dodge(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // ColorDodge
+        case 19u: { return vec4<f32>(blend_color_burn(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // ColorBurn
+        case 20u: { return vec4<f32>(blend_overlay(dest.rgb, src.rgb) * src.a, dest.a * src.a); } // HardLight
+        case 21u: { return vec4<f32>(blend_soft_light(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // SoftLight
+        case 22u: { return vec4<f32>((dest.rgb + src.rgb - 2.0 * dest.rgb * src.rgb) * src.a, dest.a * src.a); } // Exclusion
+        case 23u: { return vec4<f32>(blend_hue(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Hue
+        case 24u: { return vec4<f32>(blend_saturation(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Saturation
+        case 25u: { return vec4<f32>(blend_color(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Color
+        case 26u: { return vec4<f32>(blend_luminosity(src.rgb, dest.rgb) * src.a, dest.a * src.a); } // Luminosity
+        default: { return src; }
+    }
+}
+
+fn fill_mask(input: VertexOutput) -> vec4<f32> {
+    let col = fill_image(input);
+    let alpha = sample1(input.obj_coord).a;
+    return col * alpha;
+}
+
+fn fill_glyph(input: VertexOutput) -> vec4<f32> {
+    let alpha = sample0(input.tex_coord).a * input.color.a;
+    let fill_color_luma = input.data0.y;
+    let corrected_alpha = sample1(vec2<f32>(alpha, fill_color_luma)).a;
+
+    return vec4<f32>(input.color.rgb * corrected_alpha, corrected_alpha);
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    var out_color = input.color;
+    var discarded = false;
+
+    switch fill_type(input) {
+        case 0u: { out_color = fill_solid(input); }
+        case 1u: { out_color = fill_image(input); }
+        case 2u: { out_color = fill_pattern_image(input); }
+        case 3u: { out_color = fill_pattern_gradient(input); }
+        case 7u: { out_color = fill_rounded_rect(input); }
+        case 8u: {
+            let d = box_shadow_distances(input);
+            discarded = d.x < 0.0;
+            out_color = fill_box_shadow(input, d.y);
+        }
+        case 9u: { out_color = fill_blend(input); }
+        case 10u: { out_color = fill_mask(input); }
+        case 11u: { out_color = fill_glyph(input); }
+        default: {}
+    }
+
+    // Clip before discarding, `fwidth` has to run in uniform control flow.
+    out_color = apply_clip(input.obj_coord, out_color);
+    if (discarded) {
+        discard;
+    }
+    return out_color;
+}
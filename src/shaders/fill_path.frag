@@ -0,0 +1,197 @@
+#version 450 core
+struct Uniforms {
+    vec4 state;
+    mat4x4 transform;
+    vec4 scalar4_[2];
+    vec4 vector[8];
+    uint clip_size;
+    mat4x4 clip[8];
+};
+struct VertexOutput {
+    vec4 position;
+    vec4 color;
+    vec2 obj_coord;
+};
+layout(std140, binding = 0) uniform Uniforms_block_0Fragment { Uniforms _group_0_binding_0_fs; };
+
+layout(location = 0) smooth in vec4 _vs2fs_location0;
+layout(location = 1) smooth in vec2 _vs2fs_location1;
+layout(location = 0) out vec4 _fs2p_location0;
+
+float scalar(uint i) {
+    float _e9 = _group_0_binding_0_fs.scalar4_[(i / 4u)][(i % 4u)];
+    return _e9;
+}
+
+float sd_rect(vec2 p, vec2 size) {
+    vec2 d_1 = (abs(p) - size);
+    return (min(max(d_1.x, d_1.y), 0.0) + length(max(d_1, vec2(0.0))));
+}
+
+float sd_ellipse(vec2 p_in, vec2 ab_in) {
+    vec2 p_1 = vec2(0.0);
+    vec2 ab = vec2(0.0);
+    float co = 0.0;
+    if ((abs((ab_in.x - ab_in.y)) < 0.1)) {
+        return (length(p_in) - ab_in.x);
+    }
+    p_1 = abs(p_in);
+    ab = ab_in;
+    float _e15 = p_1.x;
+    float _e17 = p_1.y;
+    if ((_e15 > _e17)) {
+        vec2 _e19 = p_1;
+        p_1 = _e19.yx;
+        vec2 _e21 = ab;
+        ab = _e21.yx;
+    }
+    float _e24 = ab.y;
+    float _e26 = ab.y;
+    float _e29 = ab.x;
+    float _e31 = ab.x;
+    float l = ((_e24 * _e26) - (_e29 * _e31));
+    float _e35 = ab.x;
+    float _e37 = p_1.x;
+    float m = ((_e35 * _e37) / l);
+    float _e41 = ab.y;
+    float _e43 = p_1.y;
+    float n = ((_e41 * _e43) / l);
+    float m2_ = (m * m);
+    float n2_ = (n * n);
+    float c_1 = (((m2_ + n2_) - 1.0) / 3.0);
+    float c3_ = ((c_1 * c_1) * c_1);
+    float q = (c3_ + ((m2_ * n2_) * 2.0));
+    float d_2 = (c3_ + (m2_ * n2_));
+    float g = (m + (m * n2_));
+    if ((d_2 < 0.0)) {
+        float h = (acos((q / c3_)) / 3.0);
+        float s = cos(h);
+        float t = (sin(h) * sqrt(3.0));
+        float rx_1 = sqrt(((-(c_1) * ((s + t) + 2.0)) + m2_));
+        float ry_1 = sqrt(((-(c_1) * ((s - t) + 2.0)) + m2_));
+        co = ((((ry_1 + (sign(l) * rx_1)) + (abs(g) / (rx_1 * ry_1))) - m) / 2.0);
+    } else {
+        float h_1 = (((2.0 * m) * n) * sqrt(d_2));
+        float s_1 = (sign((q + h_1)) * pow(abs((q + h_1)), 0.33333334));
+        float t_1 = (sign((q - h_1)) * pow(abs((q - h_1)), 0.33333334));
+        float rx_2 = (((-(s_1) - t_1) - (c_1 * 4.0)) + (2.0 * m2_));
+        float ry_2 = ((s_1 - t_1) * sqrt(3.0));
+        float rm = sqrt(((rx_2 * rx_2) + (ry_2 * ry_2)));
+        float k = (ry_2 / sqrt((rm - rx_2)));
+        co = (((k + ((2.0 * g) / rm)) - m) / 2.0);
+    }
+    float _e144 = co;
+    float _e145 = co;
+    float si = sqrt((1.0 - (_e144 * _e145)));
+    float _e151 = ab.x;
+    float _e152 = co;
+    float _e155 = ab.y;
+    vec2 r = vec2((_e151 * _e152), (_e155 * si));
+    vec2 _e158 = p_1;
+    float _e162 = p_1.y;
+    return (length((r - _e158)) * sign((_e162 - r.y)));
+}
+
+float sd_round_rect(vec2 p_2, vec2 size_in, vec4 rx, vec4 ry) {
+    vec2 corner = vec2(0.0);
+    vec2 size_1 = (size_in * 0.5);
+    corner = vec2((-(size_1.x) + rx.x), (-(size_1.y) + ry.x));
+    float _e23 = corner.x;
+    float _e28 = corner.y;
+    if (((((rx.x * ry.x) > 0.0) && (p_2.x < _e23)) && (p_2.y <= _e28))) {
+        vec2 _e31 = corner;
+        float _e36 = sd_ellipse((p_2 - _e31), vec2(rx.x, ry.x));
+        return _e36;
+    }
+    corner = vec2((size_1.x - rx.y), (-(size_1.y) + ry.y));
+    float _e52 = corner.x;
+    float _e57 = corner.y;
+    if (((((rx.y * ry.y) > 0.0) && (p_2.x >= _e52)) && (p_2.y <= _e57))) {
+        vec2 _e60 = corner;
+        float _e65 = sd_ellipse((p_2 - _e60), vec2(rx.y, ry.y));
+        return _e65;
+    }
+    corner = vec2((size_1.x - rx.z), (size_1.y - ry.z));
+    float _e80 = corner.x;
+    float _e85 = corner.y;
+    if (((((rx.z * ry.z) > 0.0) && (p_2.x >= _e80)) && (p_2.y >= _e85))) {
+        vec2 _e88 = corner;
+        float _e93 = sd_ellipse((p_2 - _e88), vec2(rx.z, ry.z));
+        return _e93;
+    }
+    corner = vec2((-(size_1.x) + rx.w), (size_1.y - ry.w));
+    float _e109 = corner.x;
+    float _e114 = corner.y;
+    if (((((rx.w * ry.w) > 0.0) && (p_2.x < _e109)) && (p_2.y > _e114))) {
+        vec2 _e117 = corner;
+        float _e122 = sd_ellipse((p_2 - _e117), vec2(rx.w, ry.w));
+        return _e122;
+    }
+    float _e123 = sd_rect(p_2, size_1);
+    return _e123;
+}
+
+vec2 transform_affine(vec2 val, vec2 a, vec2 b, vec2 c) {
+    return (((val.x * a) + (val.y * b)) + c);
+}
+
+float antialias2_(float d) {
+    float _e3 = fwidth(d);
+    return smoothstep(-0.6180469, 0.6180469, (d / _e3));
+}
+
+vec4 apply_clip(vec2 obj_coord_1, vec4 color_1) {
+    vec4 out_color = vec4(0.0);
+    uint i_1 = 0u;
+    vec2 p_3 = vec2(0.0);
+    float d_clip = 0.0;
+    out_color = color_1;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e54 = i_1;
+            i_1 = (_e54 + 1u);
+        }
+        loop_init = false;
+        uint _e5 = i_1;
+        uint _e8 = _group_0_binding_0_fs.clip_size;
+        if ((_e5 < _e8)) {
+        } else {
+            break;
+        }
+        {
+            uint _e12 = i_1;
+            mat4x4 data = _group_0_binding_0_fs.clip[_e12];
+            vec2 origin = data[0].xy;
+            vec2 size_2 = data[0].zw;
+            vec4 radii_x = floor((data[1] / vec4(65536.0)));
+            vec4 radii_y = floor((data[1] - (radii_x * 65536.0)));
+            bool inverse_ = (data[3].z != 0.0);
+            vec2 _e39 = transform_affine(obj_coord_1, data[2].xy, data[2].zw, data[3].xy);
+            p_3 = _e39;
+            vec2 _e41 = p_3;
+            p_3 = (_e41 - origin);
+            vec2 _e43 = p_3;
+            float _e44 = sd_round_rect(_e43, size_2, radii_x, radii_y);
+            d_clip = _e44;
+            if (inverse_) {
+                float _e46 = d_clip;
+                d_clip = -(_e46);
+            }
+            float _e48 = d_clip;
+            float _e50 = antialias2_(-(_e48));
+            vec4 _e51 = out_color;
+            out_color = (_e51 * _e50);
+        }
+    }
+    vec4 _e56 = out_color;
+    return _e56;
+}
+
+void main() {
+    VertexOutput input_ = VertexOutput(gl_FragCoord, _vs2fs_location0, _vs2fs_location1);
+    vec4 _e3 = apply_clip(input_.obj_coord, input_.color);
+    _fs2p_location0 = _e3;
+    return;
+}
+
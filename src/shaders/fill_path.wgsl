@@ -0,0 +1,25 @@
+// Port of `vertex_fill_path.glsl` and `fragment_fill_path.glsl`, appended to `common.wgsl`.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) obj_coord: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<u32>,
+    @location(2) obj_coord: vec2<f32>,
+) -> VertexOutput {
+    var output: VertexOutput;
+    output.position = u.transform * vec4<f32>(position, 0.0, 1.0);
+    output.color = vec4<f32>(color) / 255.0;
+    output.obj_coord = obj_coord;
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    return apply_clip(input.obj_coord, input.color);
+}
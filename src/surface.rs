@@ -0,0 +1,86 @@
+use crate::{
+    sys::{
+        ulSurfaceClearDirtyBounds, ulSurfaceGetDirtyBounds, ulSurfaceGetHeight,
+        ulSurfaceGetRowBytes, ulSurfaceGetSize, ulSurfaceGetWidth, ulSurfaceLockPixels,
+        ulSurfaceUnlockPixels, ULSurface,
+    },
+    IntRect,
+};
+
+/// Surface pixels are always premultiplied BGRA.
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Copy of the part of a surface that changed since it was last cleared.
+///
+/// Taking it clears the dirty bounds, so the next region only contains new changes. The pixels are
+/// copied out because rendering or resizing the view can rewrite the surface at any time.
+pub struct DirtyRegion {
+    rect: IntRect,
+    /// Tightly packed premultiplied BGRA.
+    pixels: Vec<u8>,
+}
+
+impl DirtyRegion {
+    /// Returns `None` when nothing changed.
+    ///
+    /// # Safety
+    /// `surface` must be valid and not locked by others.
+    pub(crate) unsafe fn take(surface: ULSurface) -> Option<Self> {
+        let bounds = IntRect::from(ulSurfaceGetDirtyBounds(surface));
+        let rect = IntRect {
+            left: bounds.left.max(0),
+            top: bounds.top.max(0),
+            right: bounds.right.min(ulSurfaceGetWidth(surface) as i32),
+            bottom: bounds.bottom.min(ulSurfaceGetHeight(surface) as i32),
+        };
+        if rect.is_empty() {
+            return None;
+        }
+
+        let locked = ulSurfaceLockPixels(surface) as *const u8;
+        if locked.is_null() {
+            return None;
+        }
+        let surface_pixels = std::slice::from_raw_parts(locked, ulSurfaceGetSize(surface));
+        let row_bytes = ulSurfaceGetRowBytes(surface) as usize;
+        let width_bytes = rect.width() as usize * BYTES_PER_PIXEL;
+
+        let mut pixels = Vec::with_capacity(width_bytes * rect.height() as usize);
+        for y in rect.top as usize..rect.bottom as usize {
+            let start = y * row_bytes + rect.left as usize * BYTES_PER_PIXEL;
+            pixels.extend_from_slice(&surface_pixels[start..start + width_bytes]);
+        }
+
+        ulSurfaceUnlockPixels(surface);
+        ulSurfaceClearDirtyBounds(surface);
+
+        Some(Self { rect, pixels })
+    }
+
+    /// Changed area of the surface in pixels.
+    pub fn rect(&self) -> IntRect {
+        self.rect
+    }
+
+    /// Bytes between the start of two rows in `data`, the rows are tightly packed.
+    pub fn row_bytes(&self) -> usize {
+        self.rect.width() as usize * BYTES_PER_PIXEL
+    }
+
+    /// Pixels starting at the top left of the region, rows are `row_bytes` apart.
+    ///
+    /// Handy for texture uploads that take an offset and a row pitch.
+    pub fn data(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Iterate over the changed pixels of each row, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.pixels.chunks(self.row_bytes().max(1))
+    }
+
+    /// Take the tightly packed pixels.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+}
@@ -0,0 +1,874 @@
+use std::{borrow::Cow, collections::HashMap, num::NonZeroU64, sync::Arc, time::Instant};
+
+use crate::{
+    gpu_driver::{
+        BatchedPass, CommandBatch, CommandList, GeometryChanges, GpuDriver, PipelineKey,
+        RenderBufferId, RenderTarget, ResolveRenderTarget, ResourceMap, ShaderType, TextureId,
+        Vertex2f4ub2f, Vertex2f4ub2f2f28f, VertexAttribute, VertexBuffer, VertexFormat,
+    },
+    shaders::{Uniforms, FILL_PATH_WGSL, FILL_WGSL},
+    sys::ULRenderBuffer,
+    Bitmap, BitmapFormat, IntRect, ViewRef,
+};
+
+const DEPTH_STENCIL_FORMAT: ::wgpu::TextureFormat = ::wgpu::TextureFormat::Depth24PlusStencil8;
+
+struct Texture {
+    texture: ::wgpu::Texture,
+    view: Arc<::wgpu::TextureView>,
+}
+
+struct RenderBuffer {
+    texture_id: TextureId,
+    sample_count: u32,
+    /// Multisampled color target resolved into the texture, only when `sample_count > 1`.
+    msaa: Option<::wgpu::TextureView>,
+    depth_stencil: Option<::wgpu::TextureView>,
+}
+
+struct Geometry {
+    vertices: ::wgpu::Buffer,
+    indices: ::wgpu::Buffer,
+    index_count: u32,
+}
+
+/// `PipelineKey` completed with the render buffer, scissor testing is dynamic state in wgpu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct PipelineDesc {
+    shader_type: ShaderType,
+    blend: bool,
+    format: ::wgpu::TextureFormat,
+    sample_count: u32,
+    depth_stencil: bool,
+}
+
+/// `GpuDriver` rendering with wgpu, enabled with the `wgpu` feature.
+///
+/// Pipelines, bind groups and the uniform buffer are cached across frames and every command list
+/// is submitted to the queue right away. Render targets of views can be used for compositing
+/// through `texture_view` or `render_target_view`.
+pub struct WgpuDriver {
+    device: Arc<::wgpu::Device>,
+    queue: Arc<::wgpu::Queue>,
+    sample_count: u32,
+    scale: f32,
+    /// Shader time is counted from here.
+    start: Instant,
+
+    fill_shader: ::wgpu::ShaderModule,
+    fill_path_shader: ::wgpu::ShaderModule,
+    uniform_layout: ::wgpu::BindGroupLayout,
+    texture_layout: ::wgpu::BindGroupLayout,
+    pipeline_layout: ::wgpu::PipelineLayout,
+    sampler: ::wgpu::Sampler,
+    fallback_texture: ::wgpu::TextureView,
+
+    uniform_stride: u64,
+    uniform_buffer: ::wgpu::Buffer,
+    uniform_bind_group: ::wgpu::BindGroup,
+    uniforms: Vec<u8>,
+    batch: CommandBatch,
+
+    pipelines: HashMap<PipelineDesc, ::wgpu::RenderPipeline>,
+    bind_groups: HashMap<(Option<TextureId>, Option<TextureId>), ::wgpu::BindGroup>,
+
+    textures: ResourceMap<Texture>,
+    render_buffers: ResourceMap<RenderBuffer>,
+    geometries: ResourceMap<Geometry>,
+}
+
+impl WgpuDriver {
+    pub fn new(device: Arc<::wgpu::Device>, queue: Arc<::wgpu::Queue>) -> Self {
+        let shader = |label, source| {
+            device.create_shader_module(::wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: ::wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+            })
+        };
+        let fill_shader = shader("ultralight fill", FILL_WGSL);
+        let fill_path_shader = shader("ultralight fill path", FILL_PATH_WGSL);
+
+        let uniform_layout = device.create_bind_group_layout(&::wgpu::BindGroupLayoutDescriptor {
+            label: Some("ultralight uniforms"),
+            entries: &[::wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ::wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: ::wgpu::BindingType::Buffer {
+                    ty: ::wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: NonZeroU64::new(Uniforms::SIZE),
+                },
+                count: None,
+            }],
+        });
+
+        let texture_entry = |binding| ::wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ::wgpu::ShaderStages::FRAGMENT,
+            ty: ::wgpu::BindingType::Texture {
+                sample_type: ::wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: ::wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let texture_layout = device.create_bind_group_layout(&::wgpu::BindGroupLayoutDescriptor {
+            label: Some("ultralight textures"),
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                ::wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ::wgpu::ShaderStages::FRAGMENT,
+                    ty: ::wgpu::BindingType::Sampler(::wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&::wgpu::PipelineLayoutDescriptor {
+            label: Some("ultralight"),
+            bind_group_layouts: &[&uniform_layout, &texture_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sampler = device.create_sampler(&::wgpu::SamplerDescriptor {
+            label: Some("ultralight"),
+            mag_filter: ::wgpu::FilterMode::Linear,
+            min_filter: ::wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let fallback_texture = device
+            .create_texture(&::wgpu::TextureDescriptor {
+                label: Some("ultralight fallback texture"),
+                size: ::wgpu::Extent3d::default(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: ::wgpu::TextureDimension::D2,
+                format: ::wgpu::TextureFormat::Bgra8Unorm,
+                usage: ::wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&Default::default());
+
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let uniform_stride = Uniforms::SIZE.div_ceil(alignment) * alignment;
+        let (uniform_buffer, uniform_bind_group) =
+            create_uniform_buffer(&device, &uniform_layout, uniform_stride * 64);
+
+        Self {
+            device,
+            queue,
+            sample_count: 1,
+            scale: 1.0,
+            start: Instant::now(),
+            fill_shader,
+            fill_path_shader,
+            uniform_layout,
+            texture_layout,
+            pipeline_layout,
+            sampler,
+            fallback_texture,
+            uniform_stride,
+            uniform_buffer,
+            uniform_bind_group,
+            uniforms: Vec::new(),
+            batch: CommandBatch::default(),
+            pipelines: HashMap::new(),
+            bind_groups: HashMap::new(),
+            textures: ResourceMap::new(),
+            render_buffers: ResourceMap::new(),
+            geometries: ResourceMap::new(),
+        }
+    }
+
+    /// Device scale passed to the shaders, should match the scale the views are rendered at.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Number of samples used for render buffers created from now on, 1 disables MSAA.
+    ///
+    /// The count has to be supported for `Bgra8UnormSrgb` render attachments by the adapter.
+    pub fn set_sample_count(&mut self, sample_count: u32) {
+        self.sample_count = sample_count.max(1);
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    pub fn device(&self) -> &Arc<::wgpu::Device> {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &Arc<::wgpu::Queue> {
+        &self.queue
+    }
+
+    pub fn texture(&self, id: TextureId) -> Option<&::wgpu::Texture> {
+        self.textures.get(id).map(|texture| &texture.texture)
+    }
+
+    /// View of a texture, the view stays valid after the texture is destroyed by Ultralight.
+    pub fn texture_view(&self, id: TextureId) -> Option<Arc<::wgpu::TextureView>> {
+        self.textures.get(id).map(|texture| texture.view.clone())
+    }
+
+    /// View of the texture a render buffer resolves into.
+    pub fn render_buffer_view(&self, id: RenderBufferId) -> Option<Arc<::wgpu::TextureView>> {
+        self.texture_view(self.render_buffers.get(id)?.texture_id)
+    }
+
+    fn ensure_pipeline(&mut self, key: PipelineDesc) {
+        if self.pipelines.contains_key(&key) {
+            return;
+        }
+
+        let (module, stride, attributes) = match key.shader_type {
+            ShaderType::Fill => (
+                &self.fill_shader,
+                Vertex2f4ub2f2f28f::STRIDE,
+                &Vertex2f4ub2f2f28f::ATTRIBUTES[..],
+            ),
+            ShaderType::FillPath => (
+                &self.fill_path_shader,
+                Vertex2f4ub2f::STRIDE,
+                &Vertex2f4ub2f::ATTRIBUTES[..],
+            ),
+        };
+        let attributes = attributes.iter().map(vertex_attribute).collect::<Vec<_>>();
+
+        let blend = key.blend.then_some(::wgpu::BlendState {
+            color: ::wgpu::BlendComponent {
+                src_factor: ::wgpu::BlendFactor::One,
+                dst_factor: ::wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: ::wgpu::BlendOperation::Add,
+            },
+            alpha: ::wgpu::BlendComponent {
+                src_factor: ::wgpu::BlendFactor::OneMinusDstAlpha,
+                dst_factor: ::wgpu::BlendFactor::One,
+                operation: ::wgpu::BlendOperation::Add,
+            },
+        });
+
+        let pipeline = self
+            .device
+            .create_render_pipeline(&::wgpu::RenderPipelineDescriptor {
+                label: Some("ultralight"),
+                layout: Some(&self.pipeline_layout),
+                vertex: ::wgpu::VertexState {
+                    module,
+                    entry_point: "vs_main",
+                    buffers: &[::wgpu::VertexBufferLayout {
+                        array_stride: stride as u64,
+                        step_mode: ::wgpu::VertexStepMode::Vertex,
+                        attributes: &attributes,
+                    }],
+                },
+                fragment: Some(::wgpu::FragmentState {
+                    module,
+                    entry_point: "fs_main",
+                    targets: &[Some(::wgpu::ColorTargetState {
+                        format: key.format,
+                        blend,
+                        write_mask: ::wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: ::wgpu::PrimitiveState::default(),
+                // Ultralight never asks for depth or stencil testing, the attachment only has to match.
+                depth_stencil: key.depth_stencil.then(|| ::wgpu::DepthStencilState {
+                    format: DEPTH_STENCIL_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare: ::wgpu::CompareFunction::Always,
+                    stencil: Default::default(),
+                    bias: Default::default(),
+                }),
+                multisample: ::wgpu::MultisampleState {
+                    count: key.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+            });
+
+        self.pipelines.insert(key, pipeline);
+    }
+
+    fn ensure_bind_group(&mut self, textures: (Option<TextureId>, Option<TextureId>)) {
+        if self.bind_groups.contains_key(&textures) {
+            return;
+        }
+
+        let view = |id: Option<TextureId>| {
+            id.and_then(|id| self.textures.get(id))
+                .map_or(&self.fallback_texture, |texture| &*texture.view)
+        };
+        let bind_group = self.device.create_bind_group(&::wgpu::BindGroupDescriptor {
+            label: Some("ultralight textures"),
+            layout: &self.texture_layout,
+            entries: &[
+                ::wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: ::wgpu::BindingResource::TextureView(view(textures.0)),
+                },
+                ::wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: ::wgpu::BindingResource::TextureView(view(textures.1)),
+                },
+                ::wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: ::wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+
+        self.bind_groups.insert(textures, bind_group);
+    }
+
+    /// Forget every bind group sampling the texture, it was destroyed or replaced.
+    fn invalidate_texture(&mut self, id: TextureId) {
+        self.bind_groups
+            .retain(|&(texture_1, texture_2), _| texture_1 != Some(id) && texture_2 != Some(id));
+    }
+
+    /// Pipeline for `key` when drawing into `render_buffer`.
+    fn pipeline_desc(
+        &self,
+        key: PipelineKey,
+        render_buffer: &RenderBuffer,
+    ) -> Option<PipelineDesc> {
+        Some(PipelineDesc {
+            shader_type: key.shader_type,
+            blend: key.enable_blend,
+            format: self
+                .textures
+                .get(render_buffer.texture_id)?
+                .texture
+                .format(),
+            sample_count: render_buffer.sample_count,
+            depth_stencil: render_buffer.depth_stencil.is_some(),
+        })
+    }
+
+    /// Create the pipelines and bind groups used by `batch`.
+    fn prepare(&mut self, batch: &CommandBatch) {
+        for pass in batch.passes() {
+            for draw in &pass.draws {
+                let Some(render_buffer) = self.render_buffers.get(pass.render_buffer_id) else {
+                    break;
+                };
+                let Some(desc) = self.pipeline_desc(draw.pipeline, render_buffer) else {
+                    break;
+                };
+                self.ensure_pipeline(desc);
+                self.ensure_bind_group((draw.textures[0], draw.textures[1]));
+            }
+        }
+    }
+
+    /// Upload the uniforms of a batch, each at a multiple of `uniform_stride`.
+    fn upload_uniforms(&mut self, uniforms: impl IntoIterator<Item = Uniforms>) {
+        self.uniforms.clear();
+        for uniforms in uniforms {
+            self.uniforms.extend_from_slice(uniforms.as_bytes());
+            self.uniforms.resize(
+                self.uniforms
+                    .len()
+                    .next_multiple_of(self.uniform_stride as usize),
+                0,
+            );
+        }
+        if self.uniforms.is_empty() {
+            return;
+        }
+
+        let size = self.uniforms.len() as u64;
+        if size > self.uniform_buffer.size() {
+            let (buffer, bind_group) =
+                create_uniform_buffer(&self.device, &self.uniform_layout, size.next_power_of_two());
+            self.uniform_buffer = buffer;
+            self.uniform_bind_group = bind_group;
+        }
+
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, &self.uniforms);
+    }
+
+    fn record_pass(&self, encoder: &mut ::wgpu::CommandEncoder, pass: &BatchedPass) {
+        let Some(render_buffer) = self.render_buffers.get(pass.render_buffer_id) else {
+            return;
+        };
+        let Some(target) = self.textures.get(render_buffer.texture_id) else {
+            return;
+        };
+
+        let (view, resolve_target) = match &render_buffer.msaa {
+            Some(msaa) => (msaa, Some(&*target.view)),
+            None => (&*target.view, None),
+        };
+
+        let mut rpass = encoder.begin_render_pass(&::wgpu::RenderPassDescriptor {
+            label: Some("ultralight"),
+            color_attachments: &[Some(::wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: ::wgpu::Operations {
+                    load: if pass.clear {
+                        ::wgpu::LoadOp::Clear(::wgpu::Color::TRANSPARENT)
+                    } else {
+                        ::wgpu::LoadOp::Load
+                    },
+                    store: ::wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: render_buffer.depth_stencil.as_ref().map(|view| {
+                ::wgpu::RenderPassDepthStencilAttachment {
+                    view,
+                    depth_ops: Some(::wgpu::Operations {
+                        load: ::wgpu::LoadOp::Clear(1.0),
+                        store: ::wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: Some(::wgpu::Operations {
+                        load: ::wgpu::LoadOp::Clear(0),
+                        store: ::wgpu::StoreOp::Discard,
+                    }),
+                }
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        let (width, height) = (target.texture.width(), target.texture.height());
+        for draw in &pass.draws {
+            let Some(geometry) = self.geometries.get(draw.geometry_id) else {
+                continue;
+            };
+            if draw.indices.end > geometry.index_count {
+                continue;
+            }
+            let Some(desc) = self.pipeline_desc(draw.pipeline, render_buffer) else {
+                continue;
+            };
+
+            let (x, y, w, h) = match draw.scissor {
+                Some(rect) => {
+                    let left = rect.left.clamp(0, width as i32) as u32;
+                    let top = rect.top.clamp(0, height as i32) as u32;
+                    let right = rect.right.clamp(0, width as i32) as u32;
+                    let bottom = rect.bottom.clamp(0, height as i32) as u32;
+                    if right <= left || bottom <= top {
+                        continue;
+                    }
+                    (left, top, right - left, bottom - top)
+                }
+                None => (0, 0, width, height),
+            };
+            let uniform_offset = draw.uniforms as u64 * self.uniform_stride;
+
+            rpass.set_pipeline(&self.pipelines[&desc]);
+            rpass.set_bind_group(0, &self.uniform_bind_group, &[uniform_offset as u32]);
+            rpass.set_bind_group(
+                1,
+                &self.bind_groups[&(draw.textures[0], draw.textures[1])],
+                &[],
+            );
+            rpass.set_viewport(
+                0.0,
+                0.0,
+                draw.viewport_width.min(width) as f32,
+                draw.viewport_height.min(height) as f32,
+                0.0,
+                1.0,
+            );
+            rpass.set_scissor_rect(x, y, w, h);
+            rpass.set_vertex_buffer(0, geometry.vertices.slice(..));
+            rpass.set_index_buffer(geometry.indices.slice(..), ::wgpu::IndexFormat::Uint32);
+            rpass.draw_indexed(draw.indices.clone(), 0, 0..1);
+        }
+    }
+}
+
+impl GpuDriver for WgpuDriver {
+    fn next_texture_id(&mut self) -> u32 {
+        self.textures.next_id()
+    }
+
+    fn create_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        // wgpu can't sample R8 as alpha, A8 bitmaps are expanded to BGRA on upload. Bitmaps of an
+        // unknown format are skipped, draws sample the fallback texture instead.
+        let format = match bitmap.format() {
+            Some(BitmapFormat::A8_UNORM) => ::wgpu::TextureFormat::Bgra8Unorm,
+            Some(BitmapFormat::BGRA8_UNORM_SRGB) => ::wgpu::TextureFormat::Bgra8UnormSrgb,
+            None => return,
+        };
+        let texture = self.device.create_texture(&::wgpu::TextureDescriptor {
+            label: Some("ultralight texture"),
+            size: ::wgpu::Extent3d {
+                width: bitmap.width().max(1),
+                height: bitmap.height().max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: ::wgpu::TextureDimension::D2,
+            format,
+            usage: if bitmap.is_empty() {
+                ::wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | ::wgpu::TextureUsages::TEXTURE_BINDING
+                    | ::wgpu::TextureUsages::COPY_SRC
+                    | ::wgpu::TextureUsages::COPY_DST
+            } else {
+                ::wgpu::TextureUsages::TEXTURE_BINDING | ::wgpu::TextureUsages::COPY_DST
+            },
+            view_formats: &[],
+        });
+        let view = Arc::new(texture.create_view(&Default::default()));
+
+        self.invalidate_texture(id);
+        self.textures.insert(id, Texture { texture, view });
+        if !bitmap.is_empty() {
+            self.update_texture(id, bitmap);
+        }
+    }
+
+    fn update_texture(&mut self, id: u32, bitmap: &Bitmap) {
+        let Some(texture) = self.textures.get(id) else {
+            return;
+        };
+        if bitmap.is_empty() {
+            return;
+        }
+        if texture.texture.width() != bitmap.width() || texture.texture.height() != bitmap.height()
+        {
+            self.create_texture(id, bitmap);
+            return;
+        }
+
+        self.write_texture(&texture.texture, bitmap, bitmap_rect(bitmap));
+    }
+
+    fn update_texture_region(&mut self, id: u32, bitmap: &Bitmap, region: IntRect) {
+        let Some(texture) = self.textures.get(id) else {
+            return;
+        };
+        let bounds = bitmap_rect(bitmap);
+        if texture.texture.width() != bitmap.width()
+            || texture.texture.height() != bitmap.height()
+            || !bounds.contains(region.left, region.top)
+            || region.right > bounds.right
+            || region.bottom > bounds.bottom
+        {
+            self.update_texture(id, bitmap);
+            return;
+        }
+
+        if !region.is_empty() {
+            self.write_texture(&texture.texture, bitmap, region);
+        }
+    }
+
+    fn destroy_texture(&mut self, id: u32) {
+        self.invalidate_texture(id);
+        self.textures.remove(id);
+    }
+
+    fn next_render_buffer_id(&mut self) -> u32 {
+        self.render_buffers.next_id()
+    }
+
+    fn create_render_buffer(&mut self, id: u32, render_buffer: ULRenderBuffer) {
+        let format = self
+            .textures
+            .get(render_buffer.texture_id)
+            .map_or(::wgpu::TextureFormat::Bgra8UnormSrgb, |texture| {
+                texture.texture.format()
+            });
+        let size = ::wgpu::Extent3d {
+            width: render_buffer.width.max(1),
+            height: render_buffer.height.max(1),
+            depth_or_array_layers: 1,
+        };
+        let attachment = |label, format| {
+            self.device
+                .create_texture(&::wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: self.sample_count,
+                    dimension: ::wgpu::TextureDimension::D2,
+                    format,
+                    usage: ::wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&Default::default())
+        };
+
+        let msaa = (self.sample_count > 1).then(|| attachment("ultralight msaa", format));
+        let depth_stencil = (render_buffer.has_depth_buffer || render_buffer.has_stencil_buffer)
+            .then(|| attachment("ultralight depth stencil", DEPTH_STENCIL_FORMAT));
+
+        self.render_buffers.insert(
+            id,
+            RenderBuffer {
+                texture_id: render_buffer.texture_id,
+                sample_count: self.sample_count,
+                msaa,
+                depth_stencil,
+            },
+        );
+    }
+
+    fn destroy_render_buffer(&mut self, id: u32) {
+        self.render_buffers.remove(id);
+    }
+
+    fn next_geometry_id(&mut self) -> u32 {
+        self.geometries.next_id()
+    }
+
+    fn create_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        let geometry = Geometry {
+            vertices: self.create_buffer(
+                "ultralight vertices",
+                ::wgpu::BufferUsages::VERTEX,
+                vertices.as_bytes(),
+            ),
+            indices: self.create_buffer(
+                "ultralight indices",
+                ::wgpu::BufferUsages::INDEX,
+                bytemuck::cast_slice(indices),
+            ),
+            index_count: indices.len() as u32,
+        };
+        self.geometries.insert(id, geometry);
+    }
+
+    fn update_geometry(&mut self, id: u32, vertices: VertexBuffer<'_>, indices: &[u32]) {
+        let Some(geometry) = self.geometries.get_mut(id) else {
+            return;
+        };
+
+        // Reuse the buffers when the new data fits, Ultralight updates geometry every frame.
+        let vertices = vertices.as_bytes();
+        let indices: &[u8] = bytemuck::cast_slice(indices);
+        if vertices.len() as u64 > geometry.vertices.size()
+            || indices.len() as u64 > geometry.indices.size()
+        {
+            let geometry = Geometry {
+                vertices: self.create_buffer(
+                    "ultralight vertices",
+                    ::wgpu::BufferUsages::VERTEX,
+                    vertices,
+                ),
+                indices: self.create_buffer(
+                    "ultralight indices",
+                    ::wgpu::BufferUsages::INDEX,
+                    indices,
+                ),
+                index_count: indices.len() as u32 / 4,
+            };
+            self.geometries.insert(id, geometry);
+            return;
+        }
+
+        self.queue.write_buffer(&geometry.vertices, 0, vertices);
+        self.queue.write_buffer(&geometry.indices, 0, indices);
+        geometry.index_count = indices.len() as u32 / 4;
+    }
+
+    fn update_geometry_ranges(
+        &mut self,
+        id: u32,
+        vertices: VertexBuffer<'_>,
+        indices: &[u32],
+        changes: &GeometryChanges,
+    ) {
+        let Some(geometry) = self.geometries.get(id) else {
+            return;
+        };
+        let vertices_bytes = vertices.as_bytes();
+        let indices_bytes: &[u8] = bytemuck::cast_slice(indices);
+        if changes.vertices.end as u64 > geometry.vertices.size()
+            || changes.indices.end as u64 > geometry.indices.size()
+            || indices.len() as u32 != geometry.index_count
+        {
+            self.update_geometry(id, vertices, indices);
+            return;
+        }
+
+        if !changes.vertices.is_empty() {
+            self.queue.write_buffer(
+                &geometry.vertices,
+                changes.vertices.start as u64,
+                &vertices_bytes[changes.vertices.clone()],
+            );
+        }
+        if !changes.indices.is_empty() {
+            self.queue.write_buffer(
+                &geometry.indices,
+                changes.indices.start as u64,
+                &indices_bytes[changes.indices.clone()],
+            );
+        }
+    }
+
+    fn destroy_geometry(&mut self, id: u32) {
+        self.geometries.remove(id);
+    }
+
+    fn update_command_list(&mut self, commands: CommandList<'_>) {
+        // Taken out while recording, the render passes borrow the caches of `self`.
+        let mut batch = std::mem::take(&mut self.batch);
+        batch.update(commands);
+        if batch.is_empty() {
+            self.batch = batch;
+            return;
+        }
+
+        self.prepare(&batch);
+        let time = self.start.elapsed().as_secs_f32();
+        self.upload_uniforms(batch.uniforms(self.scale, time));
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&::wgpu::CommandEncoderDescriptor {
+                label: Some("ultralight"),
+            });
+        for pass in batch.passes() {
+            self.record_pass(&mut encoder, pass);
+        }
+        self.queue.submit(Some(encoder.finish()));
+
+        self.batch = batch;
+    }
+}
+
+impl WgpuDriver {
+    /// Buffer holding `contents`, at least 4 bytes so it can always be bound and written.
+    fn create_buffer(
+        &self,
+        label: &str,
+        usage: ::wgpu::BufferUsages,
+        contents: &[u8],
+    ) -> ::wgpu::Buffer {
+        let buffer = self.device.create_buffer(&::wgpu::BufferDescriptor {
+            label: Some(label),
+            size: (contents.len() as u64).max(::wgpu::COPY_BUFFER_ALIGNMENT),
+            usage: usage | ::wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        if !contents.is_empty() {
+            self.queue.write_buffer(&buffer, 0, contents);
+        }
+        buffer
+    }
+
+    /// Upload the pixels of `bitmap` inside `region` to the same place in `texture`.
+    fn write_texture(&self, texture: &::wgpu::Texture, bitmap: &Bitmap, region: IntRect) {
+        let pixels = bitmap.lock_pixels();
+        let (left, top) = (region.left as u32, region.top as u32);
+        let (data, offset, bytes_per_row) = if bitmap.format() == Some(BitmapFormat::A8_UNORM) {
+            let columns = region.left as usize..region.right as usize;
+            let expanded = pixels
+                .rows()
+                .skip(top as usize)
+                .take(region.height() as usize)
+                .flat_map(|row| &row[columns.clone()])
+                .flat_map(|&a| [a; 4])
+                .collect::<Vec<u8>>();
+            (Cow::Owned(expanded), 0, region.width() * 4)
+        } else {
+            // Point the layout at the first pixel of the region instead of copying it out.
+            let offset = top as u64 * bitmap.row_bytes() as u64 + (left * bitmap.bpp()) as u64;
+            (Cow::Borrowed(&pixels[..]), offset, bitmap.row_bytes())
+        };
+
+        self.queue.write_texture(
+            ::wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: ::wgpu::Origin3d {
+                    x: left,
+                    y: top,
+                    z: 0,
+                },
+                aspect: ::wgpu::TextureAspect::All,
+            },
+            &data,
+            ::wgpu::ImageDataLayout {
+                offset,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(region.height()),
+            },
+            ::wgpu::Extent3d {
+                width: region.width(),
+                height: region.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+fn bitmap_rect(bitmap: &Bitmap) -> IntRect {
+    IntRect {
+        left: 0,
+        top: 0,
+        right: bitmap.width() as i32,
+        bottom: bitmap.height() as i32,
+    }
+}
+
+fn create_uniform_buffer(
+    device: &::wgpu::Device,
+    layout: &::wgpu::BindGroupLayout,
+    size: u64,
+) -> (::wgpu::Buffer, ::wgpu::BindGroup) {
+    let buffer = device.create_buffer(&::wgpu::BufferDescriptor {
+        label: Some("ultralight uniforms"),
+        size,
+        usage: ::wgpu::BufferUsages::UNIFORM | ::wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&::wgpu::BindGroupDescriptor {
+        label: Some("ultralight uniforms"),
+        layout,
+        entries: &[::wgpu::BindGroupEntry {
+            binding: 0,
+            resource: ::wgpu::BindingResource::Buffer(::wgpu::BufferBinding {
+                buffer: &buffer,
+                offset: 0,
+                size: NonZeroU64::new(Uniforms::SIZE),
+            }),
+        }],
+    });
+    (buffer, bind_group)
+}
+
+fn vertex_attribute(attribute: &VertexAttribute) -> ::wgpu::VertexAttribute {
+    ::wgpu::VertexAttribute {
+        format: match attribute.format {
+            VertexFormat::Float32x2 => ::wgpu::VertexFormat::Float32x2,
+            VertexFormat::Float32x4 => ::wgpu::VertexFormat::Float32x4,
+            VertexFormat::Uint8x4 => ::wgpu::VertexFormat::Uint8x4,
+        },
+        offset: attribute.offset as u64,
+        shader_location: attribute.shader_location,
+    }
+}
+
+impl ResolveRenderTarget for WgpuDriver {
+    /// View of the whole texture, the view's content is at `RenderTarget::uv`.
+    type Output = Arc<::wgpu::TextureView>;
+
+    fn resolve_render_target(&self, target: &RenderTarget) -> Option<Self::Output> {
+        self.texture_view(target.texture_id)
+    }
+}
+
+/// View of the texture `view` renders into, when the `WgpuDriver` is set on this thread.
+///
+/// Only `RenderTarget::uv` of the texture is covered by the view's content.
+pub fn render_target_view(view: &ViewRef<'_>) -> Option<Arc<::wgpu::TextureView>> {
+    view.render_target()?.resolve::<WgpuDriver>()
+}